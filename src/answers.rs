@@ -0,0 +1,144 @@
+use crate::{
+    error::ParseError,
+    runner::{PartRun, RunError},
+};
+use std::{collections::BTreeMap, path::PathBuf};
+use toml::{Table, Value};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    Pass,
+    Fail { expected: String },
+    Missing,
+    TimedOut,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Answers {
+    answers: BTreeMap<(u8, u8), String>,
+}
+
+pub fn answers_path() -> PathBuf {
+    [env!("CARGO_MANIFEST_DIR"), "answers.toml"]
+        .iter()
+        .collect()
+}
+
+pub(crate) fn toml_error(text: &str, error: toml::de::Error) -> ParseError {
+    let offset = error.span().map_or(0, |span| span.start).min(text.len());
+    let line_start = text[..offset].rfind('\n').map_or(0, |pos| pos + 1);
+    let line_num = text[..offset].matches('\n').count() + 1;
+    let line = text[line_start..].lines().next().unwrap_or("");
+
+    ParseError::new(
+        text[line_start..offset].chars().count() + 1,
+        line,
+        error.message(),
+    )
+    .with_line(line_num, line)
+}
+
+pub(crate) fn parse_key(key: &str, prefix: &str) -> Option<u8> {
+    key.strip_prefix(prefix)?.parse::<u8>().ok()
+}
+
+impl Answers {
+    pub fn parse(text: &str) -> Result<Self, ParseError> {
+        let table = text.parse::<Table>().map_err(|err| toml_error(text, err))?;
+
+        let mut answers = Answers::default();
+
+        for (day_key, parts) in &table {
+            let day_num = parse_key(day_key, "day")
+                .ok_or_else(|| ParseError::new(1, day_key, "a '[day<day_num>]' table"))?;
+
+            let parts = parts
+                .as_table()
+                .ok_or_else(|| ParseError::new(1, day_key, "a '[day<day_num>]' table"))?;
+
+            for (part_key, answer) in parts {
+                let part_num = parse_key(part_key, "part")
+                    .ok_or_else(|| ParseError::new(1, part_key, "a 'part<part_num>' key"))?;
+
+                let answer = match answer {
+                    Value::Integer(answer) => answer.to_string(),
+                    Value::String(answer) => answer.clone(),
+                    _ => return Err(ParseError::new(1, part_key, "an integer or string answer")),
+                };
+
+                answers.insert(day_num, part_num, answer);
+            }
+        }
+
+        Ok(answers)
+    }
+
+    pub fn get(&self, day_num: u8, part_num: u8) -> Option<&str> {
+        self.answers.get(&(day_num, part_num)).map(String::as_str)
+    }
+
+    pub fn insert(&mut self, day_num: u8, part_num: u8, answer: String) {
+        self.answers.insert((day_num, part_num), answer);
+    }
+
+    pub fn verify(&self, run: &PartRun) -> Verdict {
+        match (self.get(run.day_num, run.part_num), &run.answer) {
+            (_, Err(RunError::TimedOut(_))) => Verdict::TimedOut,
+            (None, _) => Verdict::Missing,
+            (Some(expected), Ok(answer)) if answer.to_string() == expected => Verdict::Pass,
+            (Some(expected), _) => Verdict::Fail {
+                expected: expected.to_string(),
+            },
+        }
+    }
+}
+
+#[test]
+fn test_parse() {
+    let answers = Answers::parse("[day1]\npart1 = 514579\npart2 = \"241861950\"\n").unwrap();
+
+    assert_eq!(answers.get(1, 1), Some("514579"));
+    assert_eq!(answers.get(1, 2), Some("241861950"));
+    assert_eq!(answers.get(2, 1), None);
+}
+
+#[test]
+fn test_parse_errors() {
+    let error = Answers::parse("[day1]\npart1 = 514579\npart2 = \n").unwrap_err();
+    assert_eq!(error.line, Some(3));
+
+    let error = Answers::parse("[dayone]\npart1 = 1\n").unwrap_err();
+    assert_eq!(error.text, "dayone");
+}
+
+#[test]
+fn test_verify() {
+    let answers = Answers::parse("[day1]\npart1 = 514579\npart2 = 241861950\n").unwrap();
+    let run = |day_num, part_num, answer| PartRun {
+        day_num,
+        part_num,
+        answer,
+        diagnostics: vec![],
+        parse_time: std::time::Duration::default(),
+        solve_time: std::time::Duration::default(),
+    };
+
+    assert_eq!(
+        answers.verify(&run(1, 1, Ok(crate::answer::Answer::from(514579)))),
+        Verdict::Pass
+    );
+    assert_eq!(
+        answers.verify(&run(1, 2, Err(RunError::NoAnswer))),
+        Verdict::Fail {
+            expected: "241861950".to_string()
+        }
+    );
+    assert_eq!(
+        answers.verify(&run(2, 1, Err(RunError::NoAnswer))),
+        Verdict::Missing
+    );
+    assert_eq!(
+        answers.verify(&run(2, 1, Err(RunError::TimedOut(Default::default())))),
+        Verdict::TimedOut
+    );
+}
@@ -0,0 +1,227 @@
+use crate::{
+    answer::Answer,
+    answers::{parse_key, toml_error},
+    error::{ParseError, SolveError},
+    solver::Solver,
+};
+use std::{
+    collections::BTreeMap,
+    time::{Duration, Instant},
+};
+use toml::{Table, Value};
+
+pub const DEFAULT_WARMUP: usize = 3;
+pub const DEFAULT_ITERATIONS: usize = 10;
+pub const DEFAULT_TOLERANCE: f64 = 10.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stats {
+    pub iterations: usize,
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub stddev: Duration,
+}
+
+impl Stats {
+    pub fn from_samples(samples: &[Duration]) -> Option<Self> {
+        if samples.is_empty() {
+            return None;
+        }
+
+        let mut sorted = samples.to_vec();
+        sorted.sort();
+
+        let middle = sorted.len() / 2;
+        let median = if sorted.len().is_multiple_of(2) {
+            (sorted[middle - 1] + sorted[middle]) / 2
+        } else {
+            sorted[middle]
+        };
+
+        let secs = samples.iter().map(Duration::as_secs_f64);
+        let mean = secs.clone().sum::<f64>() / samples.len() as f64;
+        let variance =
+            secs.map(|sample| (sample - mean).powi(2)).sum::<f64>() / samples.len() as f64;
+
+        Some(Self {
+            iterations: samples.len(),
+            min: sorted[0],
+            median,
+            mean: Duration::from_secs_f64(mean),
+            stddev: Duration::from_secs_f64(variance.sqrt()),
+        })
+    }
+}
+
+pub fn run(
+    solver: &dyn Solver,
+    part_num: u8,
+    warmup: usize,
+    iterations: usize,
+) -> (Result<Option<Answer>, SolveError>, Vec<Duration>) {
+    for _ in 0..warmup {
+        let _ = solver.solve(part_num);
+    }
+
+    let mut answer = Ok(None);
+    let mut samples = vec![];
+
+    for _ in 0..iterations {
+        let start = Instant::now();
+        answer = solver.solve(part_num);
+        samples.push(start.elapsed());
+    }
+
+    (answer, samples)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Comparison {
+    Faster(f64),
+    Unchanged(f64),
+    Regressed(f64),
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Baseline {
+    medians: BTreeMap<(u8, u8), Duration>,
+}
+
+impl Baseline {
+    pub fn parse(text: &str) -> Result<Self, ParseError> {
+        let table = text.parse::<Table>().map_err(|err| toml_error(text, err))?;
+
+        let mut baseline = Baseline::default();
+
+        for (day_key, parts) in &table {
+            let day_num = parse_key(day_key, "day")
+                .ok_or_else(|| ParseError::new(1, day_key, "a '[day<day_num>]' table"))?;
+
+            let parts = parts
+                .as_table()
+                .ok_or_else(|| ParseError::new(1, day_key, "a '[day<day_num>]' table"))?;
+
+            for (part_key, median) in parts {
+                let part_num = parse_key(part_key, "part")
+                    .ok_or_else(|| ParseError::new(1, part_key, "a 'part<part_num>' key"))?;
+
+                let median_ms = match median {
+                    Value::Float(median_ms) if *median_ms >= 0.0 => *median_ms,
+                    Value::Integer(median_ms) if *median_ms >= 0 => *median_ms as f64,
+                    _ => {
+                        return Err(ParseError::new(
+                            1,
+                            part_key,
+                            "a median time in milliseconds",
+                        ))
+                    }
+                };
+
+                baseline.insert(
+                    day_num,
+                    part_num,
+                    Duration::from_secs_f64(median_ms / 1000.0),
+                );
+            }
+        }
+
+        Ok(baseline)
+    }
+
+    pub fn get(&self, day_num: u8, part_num: u8) -> Option<Duration> {
+        self.medians.get(&(day_num, part_num)).copied()
+    }
+
+    pub fn insert(&mut self, day_num: u8, part_num: u8, median: Duration) {
+        self.medians.insert((day_num, part_num), median);
+    }
+
+    pub fn compare(
+        &self,
+        day_num: u8,
+        part_num: u8,
+        stats: &Stats,
+        tolerance: f64,
+    ) -> Option<Comparison> {
+        let baseline = self.get(day_num, part_num)?.as_secs_f64();
+        let change = if baseline > 0.0 {
+            (stats.median.as_secs_f64() - baseline) / baseline * 100.0
+        } else {
+            0.0
+        };
+
+        Some(if change > tolerance {
+            Comparison::Regressed(change)
+        } else if change < -tolerance {
+            Comparison::Faster(change)
+        } else {
+            Comparison::Unchanged(change)
+        })
+    }
+
+    pub fn to_toml(&self) -> String {
+        let mut table = Table::new();
+
+        for ((day_num, part_num), median) in &self.medians {
+            let day = table
+                .entry(format!("day{}", day_num))
+                .or_insert_with(|| Value::Table(Table::new()));
+
+            if let Value::Table(day) = day {
+                day.insert(
+                    format!("part{}", part_num),
+                    Value::Float(median.as_secs_f64() * 1000.0),
+                );
+            }
+        }
+
+        table.to_string()
+    }
+}
+
+#[test]
+fn test_stats() {
+    let samples = [4, 1, 3, 2]
+        .iter()
+        .map(|ms| Duration::from_millis(*ms))
+        .collect::<Vec<_>>();
+    let stats = Stats::from_samples(&samples).unwrap();
+
+    assert_eq!(stats.min, Duration::from_millis(1));
+    assert_eq!(stats.median, Duration::from_micros(2500));
+    assert_eq!(stats.mean, Duration::from_micros(2500));
+    assert_eq!(stats.stddev.as_micros(), 1118);
+    assert_eq!(Stats::from_samples(&[]), None);
+}
+
+#[test]
+fn test_baseline() {
+    let mut baseline = Baseline::default();
+    baseline.insert(15, 2, Duration::from_millis(100));
+
+    let baseline = Baseline::parse(&baseline.to_toml()).unwrap();
+    assert_eq!(baseline.get(15, 2), Some(Duration::from_millis(100)));
+
+    let stats = |ms| Stats {
+        iterations: 1,
+        min: Duration::from_millis(ms),
+        median: Duration::from_millis(ms),
+        mean: Duration::from_millis(ms),
+        stddev: Duration::default(),
+    };
+
+    assert!(matches!(
+        baseline.compare(15, 2, &stats(120), 10.0),
+        Some(Comparison::Regressed(change)) if (change - 20.0).abs() < 1e-6
+    ));
+    assert!(matches!(
+        baseline.compare(15, 2, &stats(105), 10.0),
+        Some(Comparison::Unchanged(_))
+    ));
+    assert!(matches!(
+        baseline.compare(15, 2, &stats(50), 10.0),
+        Some(Comparison::Faster(_))
+    ));
+    assert_eq!(baseline.compare(15, 1, &stats(105), 10.0), None);
+}
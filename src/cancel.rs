@@ -0,0 +1,91 @@
+use std::{
+    cell::RefCell,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread,
+    time::Duration,
+};
+
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken {
+    cancelled: Arc<AtomicBool>,
+    timeout: Option<Duration>,
+}
+
+thread_local! {
+    static CURRENT: RefCell<Option<CancelToken>> = const { RefCell::new(None) };
+}
+
+impl CancelToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_timeout(timeout: Duration) -> Self {
+        Self {
+            cancelled: Arc::new(AtomicBool::new(false)),
+            timeout: Some(timeout),
+        }
+    }
+
+    pub fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    pub fn start_timer(&self) {
+        if let Some(timeout) = self.timeout {
+            let token = self.clone();
+
+            thread::spawn(move || {
+                thread::sleep(timeout);
+                token.cancel();
+            });
+        }
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    pub fn scope<T, F: FnOnce() -> T>(&self, f: F) -> T {
+        let previous = CURRENT.with(|current| current.replace(Some(self.clone())));
+        let result = f();
+        CURRENT.with(|current| *current.borrow_mut() = previous);
+
+        result
+    }
+}
+
+pub fn is_cancelled() -> bool {
+    CURRENT.with(|current| {
+        current
+            .borrow()
+            .as_ref()
+            .is_some_and(CancelToken::is_cancelled)
+    })
+}
+
+#[test]
+fn test_scope() {
+    let token = CancelToken::new();
+
+    assert!(!token.scope(is_cancelled));
+    token.cancel();
+    assert!(token.scope(is_cancelled));
+    assert!(!is_cancelled());
+}
+
+#[test]
+fn test_start_timer() {
+    let token = CancelToken::with_timeout(Duration::from_millis(10));
+    token.start_timer();
+
+    thread::sleep(Duration::from_millis(200));
+    assert!(token.is_cancelled());
+}
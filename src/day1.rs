@@ -1,38 +1,616 @@
-use crate::utils;
+use crate::{
+    answer::Answer,
+    error::{ParseError, SolveError},
+    generate::{self, Rng},
+    input::Input,
+    solver::{self, Param, Registry, Solver},
+};
+use num_bigint::{BigInt, BigUint};
+use rand::Rng as _;
+use std::{
+    collections::HashMap,
+    convert::TryFrom,
+    fmt,
+    hash::Hash,
+    ops::{Add, RangeInclusive, Sub},
+    str::FromStr,
+};
 
-pub fn part1() {
-    let numbers = utils::stdin_to_vec::<i32>();
-    for i in 0..numbers.len() {
-        let num1 = numbers[i];
+pub const TARGET: i64 = 2020;
+pub const COMBINATION_LIMIT: usize = 100;
 
-        for j in (i + 1)..numbers.len() {
-            let num2 = numbers[j];
+pub trait Wide: Clone + Ord + Hash + Default + Add<Output = Self> + Sub<Output = Self> {}
 
-            if (num1 + num2) == 2020 {
-                println!("{}", num1 * num2);
-                return;
+impl<W: Clone + Ord + Hash + Default + Add<Output = W> + Sub<Output = W>> Wide for W {}
+
+pub trait Amount:
+    Clone + Ord + Hash + FromStr + fmt::Display + From<i64> + Into<BigInt> + Into<Answer>
+{
+    type Wide: Wide;
+
+    const NAME: &'static str;
+
+    fn widen(&self) -> Self::Wide;
+
+    fn try_mul(&self, other: &Self) -> Option<Self>;
+}
+
+impl Amount for i64 {
+    type Wide = i128;
+
+    const NAME: &'static str = "i64";
+
+    fn widen(&self) -> i128 {
+        *self as i128
+    }
+
+    fn try_mul(&self, other: &Self) -> Option<Self> {
+        self.checked_mul(*other)
+    }
+}
+
+impl Amount for i128 {
+    type Wide = BigInt;
+
+    const NAME: &'static str = "i128";
+
+    fn widen(&self) -> BigInt {
+        BigInt::from(*self)
+    }
+
+    fn try_mul(&self, other: &Self) -> Option<Self> {
+        self.checked_mul(*other)
+    }
+}
+
+impl Amount for BigInt {
+    type Wide = BigInt;
+
+    const NAME: &'static str = "big";
+
+    fn widen(&self) -> BigInt {
+        self.clone()
+    }
+
+    fn try_mul(&self, other: &Self) -> Option<Self> {
+        Some(self * other)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Width {
+    Auto,
+    I64,
+    I128,
+    Big,
+}
+
+impl FromStr for Width {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "auto" => Ok(Width::Auto),
+            "i64" => Ok(Width::I64),
+            "i128" => Ok(Width::I128),
+            "big" => Ok(Width::Big),
+            _ => Err(ParseError::new(1, s, "'auto', 'i64', 'i128' or 'big'")),
+        }
+    }
+}
+
+impl fmt::Display for Width {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Width::Auto => write!(f, "auto"),
+            Width::I64 => write!(f, "{}", i64::NAME),
+            Width::I128 => write!(f, "{}", i128::NAME),
+            Width::Big => write!(f, "{}", BigInt::NAME),
+        }
+    }
+}
+
+fn sum<W: Wide>(sorted: &[(W, usize)]) -> W {
+    sorted
+        .iter()
+        .fold(W::default(), |sum, (number, _)| sum + number.clone())
+}
+
+fn find_two_sum<T: Amount>(numbers: &[T], target: &T) -> Option<Vec<usize>> {
+    let mut seen = HashMap::<T::Wide, usize>::new();
+
+    for (j, number) in numbers.iter().enumerate() {
+        let complement = target.widen() - number.widen();
+
+        if let Some(i) = seen.get(&complement) {
+            return Some(vec![*i, j]);
+        }
+
+        seen.entry(number.widen()).or_insert(j);
+    }
+
+    None
+}
+
+fn find_sorted_k_sum<W: Wide>(
+    sorted: &[(W, usize)],
+    k: usize,
+    target: W,
+    chosen: &mut Vec<usize>,
+) -> bool {
+    if sorted.len() < k {
+        return false;
+    }
+
+    if k == 2 {
+        let (mut low, mut high) = (0, sorted.len() - 1);
+
+        while low < high {
+            let sum = sorted[low].0.clone() + sorted[high].0.clone();
+
+            if sum == target {
+                chosen.extend([sorted[low].1, sorted[high].1]);
+                return true;
+            } else if sum < target {
+                low += 1;
+            } else {
+                high -= 1;
+            }
+        }
+
+        return false;
+    }
+
+    let largest = sum(&sorted[sorted.len() - (k - 1)..]);
+
+    for i in 0..=sorted.len() - k {
+        let number = sorted[i].0.clone();
+
+        if i > 0 && sorted[i - 1].0 == sorted[i].0 {
+            continue;
+        }
+
+        if sum(&sorted[i..i + k]) > target {
+            break;
+        }
+        if number.clone() + largest.clone() < target {
+            continue;
+        }
+
+        chosen.push(sorted[i].1);
+        if find_sorted_k_sum(&sorted[i + 1..], k - 1, target.clone() - number, chosen) {
+            return true;
+        }
+        chosen.pop();
+    }
+
+    false
+}
+
+fn sorted_wide<T: Amount>(numbers: &[T]) -> Vec<(T::Wide, usize)> {
+    let mut sorted = numbers
+        .iter()
+        .map(Amount::widen)
+        .zip(0..)
+        .collect::<Vec<(T::Wide, usize)>>();
+    sorted.sort_unstable();
+    sorted
+}
+
+pub fn find_k_sum<T: Amount>(numbers: &[T], k: usize, target: T) -> Option<Vec<usize>> {
+    let mut indices = match k {
+        0 => Some(vec![]).filter(|_| target.widen() == T::Wide::default()),
+        1 => numbers
+            .iter()
+            .position(|number| *number == target)
+            .map(|i| vec![i]),
+        2 => find_two_sum(numbers, &target),
+        _ => {
+            let mut chosen = vec![];
+            if find_sorted_k_sum(&sorted_wide(numbers), k, target.widen(), &mut chosen) {
+                Some(chosen)
+            } else {
+                None
+            }
+        }
+    }?;
+
+    indices.sort_unstable();
+    Some(indices)
+}
+
+fn collect_k_sums<W: Wide>(
+    sorted: &[(W, usize)],
+    sizes: &RangeInclusive<usize>,
+    target: W,
+    chosen: &mut Vec<usize>,
+    combinations: &mut Vec<Vec<usize>>,
+    limit: usize,
+) {
+    if target == W::default() && sizes.contains(&chosen.len()) {
+        let mut combination = chosen.clone();
+        combination.sort_unstable();
+        combinations.push(combination);
+    }
+
+    if chosen.len() >= *sizes.end() {
+        return;
+    }
+
+    let non_negative = sorted
+        .first()
+        .is_none_or(|(number, _)| *number >= W::default());
+
+    for (i, (number, index)) in sorted.iter().enumerate() {
+        if combinations.len() >= limit || (non_negative && *number > target) {
+            return;
+        }
+
+        chosen.push(*index);
+        collect_k_sums(
+            &sorted[i + 1..],
+            sizes,
+            target.clone() - number.clone(),
+            chosen,
+            combinations,
+            limit,
+        );
+        chosen.pop();
+    }
+}
+
+pub fn find_k_sums<T: Amount>(
+    numbers: &[T],
+    sizes: RangeInclusive<usize>,
+    target: T,
+    limit: usize,
+) -> Vec<Vec<usize>> {
+    let mut combinations = vec![];
+    collect_k_sums(
+        &sorted_wide(numbers),
+        &sizes,
+        target.widen(),
+        &mut vec![],
+        &mut combinations,
+        limit,
+    );
+
+    combinations.sort_unstable();
+    combinations
+}
+
+pub fn count_k_sums<T: Amount>(numbers: &[T], sizes: RangeInclusive<usize>, target: T) -> BigUint {
+    let zero = T::Wide::default();
+    let target = target.widen();
+    let non_negative = numbers.iter().all(|number| number.widen() >= zero);
+    let max_size = (*sizes.end()).min(numbers.len());
+
+    let mut counts = vec![HashMap::<T::Wide, BigUint>::new(); max_size + 1];
+    counts[0].insert(zero, BigUint::from(1u8));
+
+    for number in numbers {
+        let number = number.widen();
+
+        for size in (1..=max_size).rev() {
+            let extended = counts[size - 1]
+                .iter()
+                .map(|(sum, count)| (sum.clone() + number.clone(), count.clone()))
+                .filter(|(sum, _)| !non_negative || *sum <= target)
+                .collect::<Vec<(T::Wide, BigUint)>>();
+
+            for (sum, count) in extended {
+                *counts[size].entry(sum).or_default() += count;
             }
         }
     }
+
+    (*sizes.start()..=max_size)
+        .filter_map(|size| counts[size].get(&target))
+        .sum()
 }
 
-pub fn part2() {
-    let numbers = utils::stdin_to_vec::<i32>();
+pub fn checked_product<T: Amount>(numbers: &[T], indices: &[usize]) -> Option<T> {
+    indices
+        .iter()
+        .try_fold(T::from(1), |product, i| product.try_mul(&numbers[*i]))
+}
+
+pub struct Ledger<T> {
+    pub numbers: Vec<T>,
+    pub target: T,
+}
+
+impl<T: Amount> Ledger<T> {
+    pub fn parse(input: &Input) -> Result<Self, ParseError>
+    where
+        T::Err: Into<ParseError>,
+    {
+        Ok(Self {
+            numbers: input.lines::<T>()?,
+            target: input.param("target", T::from(TARGET))?,
+        })
+    }
+
+    pub fn narrow<N: Amount + for<'a> TryFrom<&'a T>>(&self) -> Option<Ledger<N>> {
+        Some(Ledger {
+            numbers: self
+                .numbers
+                .iter()
+                .map(|number| N::try_from(number).ok())
+                .collect::<Option<Vec<N>>>()?,
+            target: N::try_from(&self.target).ok()?,
+        })
+    }
 
-    for i in 0..numbers.len() {
-        let num1 = numbers[i];
+    fn product(&self, indices: &[usize], strict: bool) -> Result<Answer, SolveError> {
+        match checked_product(&self.numbers, indices) {
+            Some(product) => Ok(product.into()),
+            None if strict => Err(SolveError::Overflow(format!(
+                "product of entries {:?} overflows {}",
+                indices,
+                T::NAME
+            ))),
+            None => Ok(Answer::from(
+                indices
+                    .iter()
+                    .map(|i| -> BigInt { self.numbers[*i].clone().into() })
+                    .product::<BigInt>(),
+            )),
+        }
+    }
 
-        for j in (i + 1)..numbers.len() {
-            let num2 = numbers[j];
+    fn solve(&self, k: usize, strict: bool) -> Result<Option<Answer>, SolveError> {
+        match find_k_sum(&self.numbers, k, self.target.clone()) {
+            Some(indices) => self.product(&indices, strict).map(Some),
+            None => Ok(None),
+        }
+    }
 
-            for k in (j + 1)..numbers.len() {
-                let num3 = numbers[k];
+    fn value(&self, name: &str, args: &[&str]) -> Result<String, String> {
+        match name {
+            "width" => Ok(T::NAME.to_string()),
+            "k_sum" => {
+                let k = solver::value_arg(args, 0, 2)?;
+                let target = solver::value_arg(args, 1, self.target.clone())?;
 
-                if (num1 + num2 + num3) == 2020 {
-                    println!("{}", num1 * num2 * num3);
-                    return;
+                match find_k_sum(&self.numbers, k, target.clone()) {
+                    Some(indices) => Ok(indices
+                        .iter()
+                        .map(|i| format!("numbers[{}] = {}", i, self.numbers[*i]))
+                        .collect::<Vec<String>>()
+                        .join("\n")),
+                    None => Ok(format!("no {} numbers sum to {}", k, target)),
                 }
             }
+            "combinations" => {
+                let min_size = solver::value_arg(args, 0, 1)?;
+                let max_size = solver::value_arg(args, 1, self.numbers.len())?;
+                let limit = solver::value_arg(args, 2, COMBINATION_LIMIT)?;
+
+                Ok(find_k_sums(
+                    &self.numbers,
+                    min_size..=max_size,
+                    self.target.clone(),
+                    limit,
+                )
+                .iter()
+                .map(|indices| {
+                    format!(
+                        "{:?} {}",
+                        indices,
+                        indices
+                            .iter()
+                            .map(|i| self.numbers[*i].to_string())
+                            .collect::<Vec<String>>()
+                            .join(" + ")
+                    )
+                })
+                .collect::<Vec<String>>()
+                .join("\n"))
+            }
+            "combination_count" => {
+                let min_size = solver::value_arg(args, 0, 1)?;
+                let max_size = solver::value_arg(args, 1, self.numbers.len())?;
+
+                Ok(
+                    count_k_sums(&self.numbers, min_size..=max_size, self.target.clone())
+                        .to_string(),
+                )
+            }
+            _ => Err(format!("unknown value '{}'", name)),
         }
     }
 }
+
+enum Entries {
+    I64(Ledger<i64>),
+    I128(Ledger<i128>),
+    Big(Ledger<BigInt>),
+}
+
+impl Entries {
+    fn narrowest(ledger: Ledger<BigInt>) -> Self {
+        if let Some(ledger) = ledger.narrow::<i64>() {
+            Entries::I64(ledger)
+        } else if let Some(ledger) = ledger.narrow::<i128>() {
+            Entries::I128(ledger)
+        } else {
+            Entries::Big(ledger)
+        }
+    }
+}
+
+pub struct Day1 {
+    entries: Entries,
+    strict: bool,
+}
+
+impl Day1 {
+    fn solve_k(&self, k: usize) -> Result<Option<Answer>, SolveError> {
+        match &self.entries {
+            Entries::I64(ledger) => ledger.solve(k, self.strict),
+            Entries::I128(ledger) => ledger.solve(k, self.strict),
+            Entries::Big(ledger) => ledger.solve(k, self.strict),
+        }
+    }
+}
+
+impl Solver for Day1 {
+    fn params() -> Vec<Param> {
+        vec![
+            Param::new("target", TARGET),
+            Param::new("width", Width::Auto),
+        ]
+    }
+
+    fn parse(input: &Input) -> Result<Self, ParseError> {
+        let width = input.param("width", Width::Auto)?;
+
+        let entries = match width {
+            Width::Auto => Entries::narrowest(Ledger::parse(input)?),
+            Width::I64 => Entries::I64(Ledger::parse(input)?),
+            Width::I128 => Entries::I128(Ledger::parse(input)?),
+            Width::Big => Entries::Big(Ledger::parse(input)?),
+        };
+
+        Ok(Self {
+            entries,
+            strict: width != Width::Auto,
+        })
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> Option<String> {
+        Some(generate::lines(rng, size, |rng| {
+            rng.gen_range(1..TARGET).to_string()
+        }))
+    }
+
+    fn part1(&self) -> Result<Option<Answer>, SolveError> {
+        self.solve_k(2)
+    }
+
+    fn part2(&self) -> Result<Option<Answer>, SolveError> {
+        self.solve_k(3)
+    }
+
+    fn values(&self) -> Vec<&'static str> {
+        vec![
+            "width",
+            "k_sum <k> [target]",
+            "combinations [min_size] [max_size] [limit]",
+            "combination_count [min_size] [max_size]",
+        ]
+    }
+
+    fn value(&self, name: &str, args: &[&str]) -> Result<String, String> {
+        match &self.entries {
+            Entries::I64(ledger) => ledger.value(name, args),
+            Entries::I128(ledger) => ledger.value(name, args),
+            Entries::Big(ledger) => ledger.value(name, args),
+        }
+    }
+}
+
+pub fn register(registry: &mut Registry) {
+    registry.register::<Day1>(1);
+}
+
+#[test]
+fn test_examples() {
+    let example = "1721\n979\n366\n299\n675\n1456\n";
+
+    assert_eq!(crate::runner::run_example(1, 1, example, &[]), "514579");
+    assert_eq!(crate::runner::run_example(1, 2, example, &[]), "241861950");
+}
+
+#[test]
+fn test_find_k_sum() {
+    let numbers: [i64; 6] = [1721, 979, 366, 299, 675, 1456];
+
+    assert_eq!(find_k_sum(&numbers, 2, 2020), Some(vec![0, 3]));
+    assert_eq!(find_k_sum(&numbers, 3, 2020), Some(vec![1, 2, 4]));
+    assert_eq!(
+        find_k_sum(&numbers, 4, 979 + 366 + 299 + 675),
+        Some(vec![1, 2, 3, 4])
+    );
+    assert_eq!(find_k_sum(&numbers, 1, 675), Some(vec![4]));
+    assert_eq!(find_k_sum(&numbers, 0, 0), Some(vec![]));
+    assert_eq!(find_k_sum(&numbers, 2, 1721 * 2), None);
+    assert_eq!(find_k_sum(&numbers, 7, 0), None);
+
+    assert_eq!(find_k_sum(&[5i64, 5, 5], 3, 15), Some(vec![0, 1, 2]));
+    assert_eq!(
+        find_k_sum(&[i64::MAX, i64::MIN, -1], 2, -1),
+        Some(vec![0, 1])
+    );
+    assert_eq!(find_k_sum(&[i64::MAX, i64::MAX, 1], 3, 1), None);
+}
+
+#[test]
+fn test_find_k_sums() {
+    let numbers: [i64; 6] = [1721, 979, 366, 299, 675, 1456];
+
+    assert_eq!(
+        find_k_sums(&numbers, 1..=6, 2020, COMBINATION_LIMIT),
+        vec![vec![0, 3], vec![1, 2, 4]]
+    );
+    assert_eq!(count_k_sums(&numbers, 1..=6, 2020), BigUint::from(2u8));
+    assert_eq!(count_k_sums(&numbers, 3..=3, 2020), BigUint::from(1u8));
+
+    let duplicates: [i64; 4] = [5, 5, 10, 0];
+    assert_eq!(
+        find_k_sums(&duplicates, 1..=4, 10, COMBINATION_LIMIT),
+        vec![vec![0, 1], vec![0, 1, 3], vec![2], vec![2, 3],]
+    );
+    assert_eq!(count_k_sums(&duplicates, 1..=4, 10), BigUint::from(4u8));
+    assert_eq!(find_k_sums(&duplicates, 1..=4, 10, 1).len(), 1);
+
+    let signed: [i64; 4] = [-3, 3, 1, -1];
+    assert_eq!(
+        find_k_sums(&signed, 1..=4, 0, COMBINATION_LIMIT),
+        vec![vec![0, 1], vec![0, 1, 2, 3], vec![2, 3]]
+    );
+    assert_eq!(count_k_sums(&signed, 1..=4, 0), BigUint::from(3u8));
+    assert_eq!(
+        count_k_sums(&[1i64; 200], 100..=100, 100),
+        "90548514656103281165404177077484163874504589675413336841320"
+            .parse()
+            .unwrap()
+    );
+}
+
+#[test]
+fn test_widths() {
+    let example = "-4000000000000\n4000000002020\n";
+    let product = "-16000000008080000000000000";
+
+    assert_eq!(crate::runner::run_example(1, 1, example, &[]), product);
+    assert_eq!(
+        crate::runner::run_example(1, 1, example, &[("width", "i64")]),
+        "overflow: product of entries [0, 1] overflows i64"
+    );
+    assert_eq!(
+        crate::runner::run_example(1, 1, example, &[("width", "i128")]),
+        product
+    );
+    assert_eq!(
+        crate::runner::run_example(1, 1, example, &[("width", "big")]),
+        product
+    );
+
+    let wide = "100000000000000000000\n-99999999999999997980\n";
+    assert_eq!(
+        crate::runner::run_example(1, 1, wide, &[]),
+        "-9999999999999999798000000000000000000000"
+    );
+    assert!(crate::runner::run_example(1, 1, wide, &[("width", "i64")]).starts_with("parse error"));
+
+    let ledger = Ledger::<BigInt> {
+        numbers: vec![BigInt::from(1), BigInt::from(i64::MAX) + 1],
+        target: BigInt::from(TARGET),
+    };
+    assert!(ledger.narrow::<i64>().is_none());
+    assert!(ledger.narrow::<i128>().is_some());
+    assert_eq!(checked_product(&[i64::MAX, 2], &[0, 1]), None);
+    assert_eq!(checked_product(&[i64::MAX, 2], &[]), Some(1));
+}
@@ -1,101 +1,177 @@
-use std::{
-    collections::{hash_map::DefaultHasher, HashMap},
-    hash::{Hash, Hasher},
+use std::collections::HashMap;
+
+use crate::{
+    answer::Answer,
+    error::{ParseError, SolveError},
+    generate::{self, Rng},
+    input::Input,
+    solver::{Param, Registry, Solver},
 };
+use num_bigint::BigUint;
+use rand::Rng as _;
 
-use crate::utils;
+pub const MAX_JOLT_DIFF: i64 = 3;
 
-const MAX_JOLT_DIFF: i64 = 3;
-
-fn get_sorted_jolts() -> Vec<i64> {
-    let mut jolts = utils::stdin_to_vec::<i64>();
+pub fn get_sorted_jolts(input: &Input, max_jolt_diff: i64) -> Result<Vec<i64>, ParseError> {
+    let mut jolts = input.lines::<i64>()?;
 
     jolts.sort();
 
     let outlet_jolt_num = 0;
-    let device_adapter_jolt_num = jolts.last().unwrap() + MAX_JOLT_DIFF;
+    let device_adapter_jolt_num = match jolts.last() {
+        Some(max_jolt_num) => max_jolt_num + max_jolt_diff,
+        None => return Err(ParseError::new(1, "", "at least one adapter joltage")),
+    };
 
     jolts.insert(0, outlet_jolt_num);
     jolts.push(device_adapter_jolt_num);
 
-    jolts
+    Ok(jolts)
 }
 
-pub fn part1() {
-    let jolts = get_sorted_jolts();
-    let mut jolts_diff_distribution = HashMap::<i64, i64>::new();
-
-    for i in 0..jolts.len() - 1 {
-        let diff = jolts[i + 1] - jolts[i];
+fn count_arrangements<T, F>(jolts: &[i64], max_jolt_diff: i64, add: F) -> Option<T>
+where
+    T: Clone + From<u8>,
+    F: Fn(T, T) -> Option<T>,
+{
+    let mut counts: Vec<T> = Vec::with_capacity(jolts.len());
+
+    for i in 0..jolts.len() {
+        let mut count = T::from(if i == 0 { 1 } else { 0 });
+
+        for j in (0..i)
+            .rev()
+            .take_while(|j| jolts[i] - jolts[*j] <= max_jolt_diff)
+        {
+            count = add(count, counts[j].clone())?;
+        }
 
-        let diff_count = jolts_diff_distribution.entry(diff).or_insert(0);
-        *diff_count += 1;
+        counts.push(count);
     }
 
-    println!("Jolts diff distribution, {:?}", jolts_diff_distribution);
-    let result = jolts_diff_distribution[&1] * jolts_diff_distribution[&3];
-    println!("Result: {}", result);
+    counts.pop()
 }
 
-fn get_vec_hash(vec: &Vec<i64>) -> u64 {
-    let mut hasher = DefaultHasher::new();
-    Hash::hash_slice(&vec, &mut hasher);
-    hasher.finish()
+pub fn find_arrangement_count(jolts: &[i64], max_jolt_diff: i64) -> Answer {
+    crate::trace!("{:?}", jolts);
+
+    match count_arrangements(jolts, max_jolt_diff, usize::checked_add) {
+        Some(count) => Answer::from(count),
+        None => {
+            crate::debug!("Arrangement count overflows usize, counting with BigUint");
+            Answer::from(
+                count_arrangements(jolts, max_jolt_diff, |a: BigUint, b| Some(a + b))
+                    .unwrap_or_default(),
+            )
+        }
+    }
 }
 
-fn find_arrangements(jolts: &Vec<i64>, arrangements: &mut HashMap<u64, Vec<i64>>) {
-    if arrangements.is_empty() {
-        arrangements.insert(get_vec_hash(jolts), jolts.clone());
+pub struct Day10 {
+    jolts: Vec<i64>,
+    max_jolt_diff: i64,
+}
+
+impl Solver for Day10 {
+    fn params() -> Vec<Param> {
+        vec![Param::new("max_jolt_diff", MAX_JOLT_DIFF)]
     }
 
-    for i in 1..jolts.len() - 1 {
-        if (jolts[i + 1] - jolts[i - 1]) <= MAX_JOLT_DIFF {
-            let mut arrangement = jolts.clone();
-            arrangement.remove(i);
+    fn parse(input: &Input) -> Result<Self, ParseError> {
+        let max_jolt_diff = input.param("max_jolt_diff", MAX_JOLT_DIFF)?;
 
-            let arrangement_hash = get_vec_hash(&arrangement);
+        Ok(Self {
+            jolts: get_sorted_jolts(input, max_jolt_diff)?,
+            max_jolt_diff,
+        })
+    }
 
-            if !arrangements.contains_key(&arrangement_hash) {
-                find_arrangements(&arrangement, arrangements);
-                arrangements.insert(arrangement_hash, arrangement);
-            }
-        }
+    fn generate(rng: &mut Rng, size: usize) -> Option<String> {
+        let mut jolt = 0;
+        let jolts = (0..size)
+            .map(|_| {
+                jolt += rng.gen_range(1..=MAX_JOLT_DIFF);
+                jolt
+            })
+            .collect::<Vec<i64>>();
+
+        Some(
+            generate::distinct(rng, jolts, size)
+                .iter()
+                .map(|jolt| format!("{}\n", jolt))
+                .collect(),
+        )
     }
-}
 
-fn find_arrangement_count(jolts: &Vec<i64>) -> usize {
-    println!("{:?}", jolts);
-    let mut arrangement_count = 1;
+    fn part1(&self) -> Result<Option<Answer>, SolveError> {
+        let jolts = &self.jolts;
+        let mut jolts_diff_distribution = HashMap::<i64, i64>::new();
+
+        for i in 0..jolts.len() - 1 {
+            let diff = jolts[i + 1] - jolts[i];
 
-    let mut i = 0;
-    while i < jolts.len() {
-        let mut valid_sequence_len = 0;
+            let diff_count = jolts_diff_distribution.entry(diff).or_insert(0);
+            *diff_count += 1;
+        }
 
-        for j in (i + 1)..jolts.len() {
-            let jolt_diff = jolts[j] - jolts[i];
+        crate::debug!("Jolts diff distribution, {:?}", jolts_diff_distribution);
+        let result = jolts_diff_distribution.get(&1).unwrap_or(&0)
+            * jolts_diff_distribution
+                .get(&self.max_jolt_diff)
+                .unwrap_or(&0);
+        Ok(Some(Answer::from(result)))
+    }
 
-            if jolt_diff > MAX_JOLT_DIFF || j == (jolts.len() - 1) {
-                let mut arrangements: HashMap<u64, Vec<i64>> = HashMap::new();
-                find_arrangements(&jolts[i..=j].to_vec(), &mut arrangements);
+    fn part2(&self) -> Result<Option<Answer>, SolveError> {
+        Ok(Some(find_arrangement_count(
+            &self.jolts,
+            self.max_jolt_diff,
+        )))
+    }
 
-                arrangement_count *= arrangements.len();
-                break;
-            }
+    fn values(&self) -> Vec<&'static str> {
+        vec!["jolts"]
+    }
 
-            valid_sequence_len += 1;
+    fn value(&self, name: &str, _args: &[&str]) -> Result<String, String> {
+        match name {
+            "jolts" => Ok(format!("{:?}", self.jolts)),
+            _ => Err(format!("unknown value '{}'", name)),
         }
-
-        i += if valid_sequence_len > 0 {
-            valid_sequence_len
-        } else {
-            1
-        };
     }
+}
 
-    arrangement_count
+pub fn register(registry: &mut Registry) {
+    registry.register::<Day10>(10);
 }
 
-pub fn part2() {
-    let jolts = get_sorted_jolts();
-    println!("Arrangement count: {}", find_arrangement_count(&jolts));
+#[test]
+fn test_examples() {
+    let example = "16\n10\n15\n5\n1\n11\n7\n19\n6\n12\n4\n";
+    let larger = "28\n33\n18\n42\n31\n14\n46\n20\n48\n47\n24\n23\n49\n45\n19\n38\n39\n11\n1\n32\n25\n35\n8\n17\n7\n9\n4\n2\n34\n10\n3\n";
+
+    assert_eq!(crate::runner::run_example(10, 1, example, &[]), "35");
+    assert_eq!(crate::runner::run_example(10, 2, example, &[]), "8");
+    assert_eq!(crate::runner::run_example(10, 1, larger, &[]), "220");
+    assert_eq!(crate::runner::run_example(10, 2, larger, &[]), "19208");
+}
+
+#[test]
+fn test_generated_arrangement_count() {
+    let text = Day10::generate(&mut crate::generate::rng(10), 1000).unwrap();
+    let jolts = get_sorted_jolts(&Input::new(text.as_str()), MAX_JOLT_DIFF).unwrap();
+
+    let mut counts = vec![BigUint::from(1_u32)];
+    for i in 1..jolts.len() {
+        let count = (0..i)
+            .filter(|j| jolts[i] - jolts[*j] <= MAX_JOLT_DIFF)
+            .map(|j| counts[j].clone())
+            .sum::<BigUint>();
+        counts.push(count);
+    }
+
+    assert_eq!(
+        find_arrangement_count(&jolts, MAX_JOLT_DIFF),
+        Answer::from(counts.pop().unwrap())
+    );
 }
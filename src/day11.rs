@@ -1,20 +1,30 @@
-use crate::utils;
-use utils::stdin_to_vec;
-
-type SeatState = char;
-type SeatLayoutRow = Vec<SeatState>;
-
-const FLOOR: SeatState = '.';
-const EMPTY_SEAT: SeatState = 'L';
-const OCCUPIED_SEAT: SeatState = '#';
+use crate::{
+    answer::Answer,
+    cancel,
+    error::{ParseError, SolveError},
+    generate::{self, Rng},
+    input::Input,
+    solver::{self, Param, Registry, Solver},
+};
+use std::{fmt, str::FromStr};
+
+pub type SeatState = char;
+pub type SeatLayoutRow = Vec<SeatState>;
+
+pub const FLOOR: SeatState = '.';
+pub const EMPTY_SEAT: SeatState = 'L';
+pub const OCCUPIED_SEAT: SeatState = '#';
+
+pub const PART1_THRESHOLD: usize = 4;
+pub const PART2_THRESHOLD: usize = 5;
 
 #[derive(Debug, Clone, PartialEq)]
-struct SeatLayout {
+pub struct SeatLayout {
     rows: Vec<SeatLayoutRow>,
 }
 
 impl SeatLayout {
-    pub fn new(encoded_rows: &Vec<String>) -> Self {
+    pub fn new(encoded_rows: &[String]) -> Self {
         let mut rows: Vec<SeatLayoutRow> = vec![];
 
         for encoded_row in encoded_rows {
@@ -25,6 +35,10 @@ impl SeatLayout {
         Self { rows }
     }
 
+    pub fn rows(&self) -> &[SeatLayoutRow] {
+        &self.rows
+    }
+
     pub fn is_seat_occupied(&self, row: usize, col: usize) -> bool {
         if row >= self.rows.len() || col >= self.rows[row].len() {
             return false;
@@ -125,21 +139,23 @@ impl SeatLayout {
         occupied_seat_count
     }
 
-    pub fn simulate_seating_part1(&mut self) -> bool {
+    pub fn simulate_seating_part1(&mut self, threshold: usize) -> bool {
         let prev_layout = self.clone();
 
         for row in 0..self.rows.len() {
             for col in 0..self.rows[row].len() {
                 match prev_layout.rows[row][col] {
-                    EMPTY_SEAT => {
-                        if prev_layout.get_occupied_adjacent_immediate_seat_count(row, col) == 0 {
-                            self.rows[row][col] = OCCUPIED_SEAT;
-                        }
+                    EMPTY_SEAT
+                        if prev_layout.get_occupied_adjacent_immediate_seat_count(row, col)
+                            == 0 =>
+                    {
+                        self.rows[row][col] = OCCUPIED_SEAT;
                     }
-                    OCCUPIED_SEAT => {
-                        if prev_layout.get_occupied_adjacent_immediate_seat_count(row, col) >= 4 {
-                            self.rows[row][col] = EMPTY_SEAT;
-                        }
+                    OCCUPIED_SEAT
+                        if prev_layout.get_occupied_adjacent_immediate_seat_count(row, col)
+                            >= threshold =>
+                    {
+                        self.rows[row][col] = EMPTY_SEAT;
                     }
                     _ => (),
                 }
@@ -149,23 +165,23 @@ impl SeatLayout {
         *self != prev_layout
     }
 
-    pub fn simulate_seating_part2(&mut self) -> bool {
+    pub fn simulate_seating_part2(&mut self, threshold: usize) -> bool {
         let prev_layout = self.clone();
 
         for row in 0..self.rows.len() {
             for col in 0..self.rows[row].len() {
                 match prev_layout.rows[row][col] {
-                    EMPTY_SEAT => {
-                        if prev_layout.get_occupied_adjacent_first_visible_seat_count(row, col) == 0
-                        {
-                            self.rows[row][col] = OCCUPIED_SEAT;
-                        }
+                    EMPTY_SEAT
+                        if prev_layout.get_occupied_adjacent_first_visible_seat_count(row, col)
+                            == 0 =>
+                    {
+                        self.rows[row][col] = OCCUPIED_SEAT;
                     }
-                    OCCUPIED_SEAT => {
-                        if prev_layout.get_occupied_adjacent_first_visible_seat_count(row, col) >= 5
-                        {
-                            self.rows[row][col] = EMPTY_SEAT;
-                        }
+                    OCCUPIED_SEAT
+                        if prev_layout.get_occupied_adjacent_first_visible_seat_count(row, col)
+                            >= threshold =>
+                    {
+                        self.rows[row][col] = EMPTY_SEAT;
                     }
                     _ => (),
                 }
@@ -176,45 +192,164 @@ impl SeatLayout {
     }
 
     pub fn print(&self) {
-        println!();
+        crate::trace!("\n{}", self);
+        crate::trace!("Occupied seats: {}", self.get_occupied_seat_count());
+    }
+}
+
+impl fmt::Display for SeatLayout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let rows = self
+            .rows
+            .iter()
+            .map(|row| row.iter().collect::<String>())
+            .collect::<Vec<String>>();
+
+        write!(f, "{}", rows.join("\n"))
+    }
+}
+
+impl FromStr for SeatLayout {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut encoded_rows: Vec<String> = vec![];
+
+        for (line_index, line) in s.lines().enumerate() {
+            let invalid_seat = line
+                .char_indices()
+                .find(|(_, c)| ![FLOOR, EMPTY_SEAT, OCCUPIED_SEAT].contains(c));
 
-        for row in &self.rows {
-            for col in row {
-                print!("{}", col);
+            if let Some((pos, c)) = invalid_seat {
+                let fragment = &line[pos..pos + c.len_utf8()];
+                return Err(ParseError::at(line, fragment, "'.', 'L' or '#'")
+                    .with_line(line_index + 1, line));
             }
-            println!();
+
+            encoded_rows.push(line.to_string());
         }
 
-        println!("Occupied seats: {}", self.get_occupied_seat_count());
+        Ok(SeatLayout::new(&encoded_rows))
     }
 }
 
-pub fn part1() {
-    let rows = &stdin_to_vec::<String>();
-    let mut layout = SeatLayout::new(&rows);
+pub struct Day11 {
+    layout: SeatLayout,
+    part1_threshold: usize,
+    part2_threshold: usize,
+}
+
+impl Solver for Day11 {
+    fn params() -> Vec<Param> {
+        vec![
+            Param::new("part1_threshold", PART1_THRESHOLD),
+            Param::new("part2_threshold", PART2_THRESHOLD),
+        ]
+    }
+
+    fn parse(input: &Input) -> Result<Self, ParseError> {
+        Ok(Self {
+            layout: input.text().parse::<SeatLayout>()?,
+            part1_threshold: input.param("part1_threshold", PART1_THRESHOLD)?,
+            part2_threshold: input.param("part2_threshold", PART2_THRESHOLD)?,
+        })
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> Option<String> {
+        Some(generate::lines(rng, size, |rng| {
+            generate::chars(rng, size, &[EMPTY_SEAT, EMPTY_SEAT, EMPTY_SEAT, FLOOR])
+        }))
+    }
+
+    fn part1(&self) -> Result<Option<Answer>, SolveError> {
+        let mut layout = self.layout.clone();
+
+        loop {
+            if cancel::is_cancelled() {
+                return Ok(None);
+            }
+
+            let state_changed = layout.simulate_seating_part1(self.part1_threshold);
 
-    loop {
-        let state_changed = layout.simulate_seating_part1();
+            if !state_changed {
+                break;
+            }
 
-        if !state_changed {
-            break;
+            layout.print();
         }
 
-        layout.print();
+        Ok(Some(Answer::from(layout.get_occupied_seat_count())))
     }
-}
 
-pub fn part2() {
-    let rows = &stdin_to_vec::<String>();
-    let mut layout = SeatLayout::new(&rows);
+    fn part2(&self) -> Result<Option<Answer>, SolveError> {
+        let mut layout = self.layout.clone();
+
+        loop {
+            if cancel::is_cancelled() {
+                return Ok(None);
+            }
 
-    loop {
-        let state_changed = layout.simulate_seating_part2();
+            let state_changed = layout.simulate_seating_part2(self.part2_threshold);
 
-        if !state_changed {
-            break;
+            if !state_changed {
+                break;
+            }
+
+            layout.print();
         }
 
-        layout.print();
+        Ok(Some(Answer::from(layout.get_occupied_seat_count())))
     }
+
+    fn values(&self) -> Vec<&'static str> {
+        vec!["layout [rounds]", "layout2 [rounds]"]
+    }
+
+    fn value(&self, name: &str, args: &[&str]) -> Result<String, String> {
+        let rounds = solver::value_arg(args, 0, 0)?;
+        let mut layout = self.layout.clone();
+
+        for _ in 0..rounds {
+            let state_changed = match name {
+                "layout" => layout.simulate_seating_part1(self.part1_threshold),
+                "layout2" => layout.simulate_seating_part2(self.part2_threshold),
+                _ => return Err(format!("unknown value '{}'", name)),
+            };
+
+            if !state_changed {
+                break;
+            }
+        }
+
+        match name {
+            "layout" | "layout2" => Ok(format!(
+                "{}\nOccupied seats: {}",
+                layout,
+                layout.get_occupied_seat_count()
+            )),
+            _ => Err(format!("unknown value '{}'", name)),
+        }
+    }
+}
+
+pub fn register(registry: &mut Registry) {
+    registry.register::<Day11>(11);
+}
+
+#[test]
+fn test_examples() {
+    let example = "L.LL.LL.LL
+LLLLLLL.LL
+L.L.L..L..
+LLLL.LL.LL
+L.LL.LL.LL
+L.LLLLL.LL
+..L.L.....
+LLLLLLLLLL
+L.LLLLLL.L
+L.LLLLL.LL
+";
+
+    assert_eq!(crate::runner::run_example(11, 1, example, &[]), "37");
+    assert_eq!(crate::runner::run_example(11, 2, example, &[]), "26");
 }
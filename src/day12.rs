@@ -1,8 +1,17 @@
-use std::{num::ParseIntError, str::FromStr};
+use std::{fmt, str::FromStr};
+
+use crate::{
+    answer::Answer,
+    error::{ParseError, SolveError},
+    generate::{self, Rng},
+    input::Input,
+    solver::{Param, Registry, Solver},
+    utils,
+};
+use rand::{seq::SliceRandom, Rng as _};
 
-use crate::utils::stdin_to_vec;
 #[derive(Debug, Clone)]
-enum Instruction {
+pub enum Instruction {
     MoveNorth(usize),
     MoveSouth(usize),
     MoveEast(usize),
@@ -12,49 +21,63 @@ enum Instruction {
     MoveForward(usize),
 }
 
-impl Instruction {
-    pub fn from(instruction_string: &str) -> Self {
-        let action_code = &instruction_string[0..1];
-        let action_value_string = &instruction_string[1..];
-        let action_value: usize = action_value_string.parse().unwrap();
+impl FromStr for Instruction {
+    type Err = ParseError;
 
-        match action_code {
-            "N" => Instruction::MoveNorth(action_value),
-            "S" => Instruction::MoveSouth(action_value),
-            "E" => Instruction::MoveEast(action_value),
-            "W" => Instruction::MoveWest(action_value),
-            "L" => Instruction::TurnLeft(action_value),
-            "R" => Instruction::TurnRight(action_value),
-            "F" => Instruction::MoveForward(action_value),
-            _ => panic!("Unexpected instruction: {}", instruction_string),
-        }
-    }
-}
+    fn from_str(instruction_string: &str) -> Result<Self, Self::Err> {
+        let action_code_len = instruction_string
+            .chars()
+            .next()
+            .map_or(0, |action_code| action_code.len_utf8());
 
-impl FromStr for Instruction {
-    type Err = ParseIntError;
+        let action_code = &instruction_string[..action_code_len];
+        let action_value_string = &instruction_string[action_code_len..];
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(Instruction::from(s))
+        let action_value: usize = action_value_string.parse().map_err(|_| {
+            ParseError::at(instruction_string, action_value_string, "an action value")
+        })?;
+
+        if (action_code == "L" || action_code == "R") && !action_value.is_multiple_of(90) {
+            return Err(ParseError::at(
+                instruction_string,
+                action_value_string,
+                "a multiple of 90 degrees",
+            ));
+        }
+
+        match action_code {
+            "N" => Ok(Instruction::MoveNorth(action_value)),
+            "S" => Ok(Instruction::MoveSouth(action_value)),
+            "E" => Ok(Instruction::MoveEast(action_value)),
+            "W" => Ok(Instruction::MoveWest(action_value)),
+            "L" => Ok(Instruction::TurnLeft(action_value)),
+            "R" => Ok(Instruction::TurnRight(action_value)),
+            "F" => Ok(Instruction::MoveForward(action_value)),
+            _ => Err(ParseError::at(
+                instruction_string,
+                action_code,
+                "an action 'N', 'S', 'E', 'W', 'L', 'R' or 'F'",
+            )),
+        }
     }
 }
 
-const NORTH: usize = 0;
-const EAST: usize = 1;
-const SOUTH: usize = 2;
-const WEST: usize = 3;
+pub const NORTH: usize = 0;
+pub const EAST: usize = 1;
+pub const SOUTH: usize = 2;
+pub const WEST: usize = 3;
 
 #[derive(Debug)]
-struct NavigationPart1 {
+pub struct NavigationPart1 {
     instructions: Vec<Instruction>,
     position: [usize; 4],
     direction: usize,
 }
 
 impl NavigationPart1 {
-    pub fn new(instructions: &Vec<Instruction>) -> Self {
+    pub fn new(instructions: &[Instruction]) -> Self {
         Self {
-            instructions: instructions.clone(),
+            instructions: instructions.to_vec(),
             position: [0, 0, 0, 0],
             direction: EAST,
         }
@@ -62,15 +85,8 @@ impl NavigationPart1 {
 
     fn find_direction(&self, angle: i32) -> usize {
         let shift_count = angle / 90;
-        let mut direction = self.direction as i32 + shift_count;
-
-        if direction < 0 {
-            direction += self.position.len() as i32;
-        } else {
-            direction %= self.position.len() as i32;
-        }
 
-        direction as usize
+        (self.direction as i32 + shift_count).rem_euclid(self.position.len() as i32) as usize
     }
 
     pub fn navigate(&mut self) {
@@ -101,41 +117,60 @@ impl NavigationPart1 {
     }
 }
 
-type Position = [usize; 4];
-type Direction = [usize; 4];
+pub type Position = [usize; 4];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Waypoint {
+    pub east: i32,
+    pub north: i32,
+}
+
+pub const WAYPOINT: Waypoint = Waypoint { east: 10, north: 1 };
+
+impl FromStr for Waypoint {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (east, north) = utils::parse_pair::<i32>(s)?;
+        Ok(Self { east, north })
+    }
+}
+
+impl fmt::Display for Waypoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{},{}", self.east, self.north)
+    }
+}
 
 #[derive(Debug)]
-struct NavigationPart2 {
+pub struct NavigationPart2 {
     instructions: Vec<Instruction>,
     viewpoint_position: Position,
     ship_position: Position,
-    direction: Direction,
 }
 
 impl NavigationPart2 {
-    pub fn new(instructions: &Vec<Instruction>) -> Self {
+    pub fn new(instructions: &[Instruction], waypoint: Waypoint) -> Self {
         let mut instance = Self {
-            instructions: instructions.clone(),
+            instructions: instructions.to_vec(),
             viewpoint_position: [0, 0, 0, 0],
             ship_position: [0, 0, 0, 0],
-            direction: [NORTH, EAST, SOUTH, WEST],
         };
 
-        instance.viewpoint_position[NORTH] = 1;
-        instance.viewpoint_position[EAST] = 10;
+        let north_south = if waypoint.north < 0 { SOUTH } else { NORTH };
+        let east_west = if waypoint.east < 0 { WEST } else { EAST };
+
+        instance.viewpoint_position[north_south] = waypoint.north.unsigned_abs() as usize;
+        instance.viewpoint_position[east_west] = waypoint.east.unsigned_abs() as usize;
 
         instance
     }
 
     fn rotate_viewpoint(&self, angle: i32) -> Position {
-        let shift_count = angle / 90;
-        let mut viewpoint_position = self.viewpoint_position.clone();
+        let shift_count = (angle / 90).rem_euclid(self.viewpoint_position.len() as i32);
+        let mut viewpoint_position = self.viewpoint_position;
 
-        if shift_count < 0 {
-            viewpoint_position.rotate_left(shift_count.abs() as usize);
-        } else {
-            viewpoint_position.rotate_right(shift_count.abs() as usize);
-        }
+        viewpoint_position.rotate_right(shift_count as usize);
 
         viewpoint_position
     }
@@ -181,18 +216,66 @@ impl NavigationPart2 {
     }
 }
 
-pub fn part1() {
-    let instructions = stdin_to_vec::<Instruction>();
-    let mut navigation = NavigationPart1::new(&instructions);
+pub struct Day12 {
+    instructions: Vec<Instruction>,
+    waypoint: Waypoint,
+}
+
+impl Solver for Day12 {
+    fn params() -> Vec<Param> {
+        vec![Param::new("waypoint", WAYPOINT)]
+    }
+
+    fn parse(input: &Input) -> Result<Self, ParseError> {
+        Ok(Self {
+            instructions: input.lines::<Instruction>()?,
+            waypoint: input.param("waypoint", WAYPOINT)?,
+        })
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> Option<String> {
+        Some(generate::lines(rng, size, |rng| {
+            match ['N', 'S', 'E', 'W', 'L', 'R', 'F']
+                .choose(rng)
+                .unwrap_or(&'F')
+            {
+                action @ ('L' | 'R') => format!("{}{}", action, 90 * rng.gen_range(1..=3)),
+                action => format!("{}{}", action, rng.gen_range(1..=100)),
+            }
+        }))
+    }
+
+    fn part1(&self) -> Result<Option<Answer>, SolveError> {
+        let mut navigation = NavigationPart1::new(&self.instructions);
+
+        navigation.navigate();
+        Ok(Some(Answer::from(navigation.get_distance())))
+    }
+
+    fn part2(&self) -> Result<Option<Answer>, SolveError> {
+        let mut navigation = NavigationPart2::new(&self.instructions, self.waypoint);
+
+        navigation.navigate();
+        Ok(Some(Answer::from(navigation.get_distance())))
+    }
+}
+
+pub fn register(registry: &mut Registry) {
+    registry.register::<Day12>(12);
+}
+
+#[test]
+fn test_examples() {
+    let example = "F10\nN3\nF7\nR90\nF11\n";
 
-    navigation.navigate();
-    println!("Distance: {}", navigation.get_distance());
+    assert_eq!(crate::runner::run_example(12, 1, example, &[]), "25");
+    assert_eq!(crate::runner::run_example(12, 2, example, &[]), "286");
 }
 
-pub fn part2() {
-    let instructions = stdin_to_vec::<Instruction>();
-    let mut navigation = NavigationPart2::new(&instructions);
+#[test]
+fn test_full_turns() {
+    let example = "F10\nN3\nF7\nL900\nR630\nL0\nF11\n";
 
-    navigation.navigate();
-    println!("Distance: {}", navigation.get_distance());
+    assert_eq!(crate::runner::run_example(12, 1, example, &[]), "25");
+    assert_eq!(crate::runner::run_example(12, 2, example, &[]), "286");
 }
@@ -1,105 +1,232 @@
-use crate::utils::stdin_to_vec;
-
-fn get_earliest_bus_departure_time(earliest_daparture_time: usize, bus_id: usize) -> usize {
-    if earliest_daparture_time % bus_id != 0 {
+use crate::{
+    answer::Answer,
+    cancel,
+    error::{ParseError, SolveError},
+    generate::{self, Rng},
+    input::Input,
+    solver::{Registry, Solver},
+};
+use num_bigint::BigUint;
+use rand::Rng as _;
+use std::convert::TryFrom;
+
+pub fn get_earliest_bus_departure_time(earliest_daparture_time: usize, bus_id: usize) -> usize {
+    if !earliest_daparture_time.is_multiple_of(bus_id) {
         earliest_daparture_time + bus_id - earliest_daparture_time % bus_id
     } else {
         earliest_daparture_time
     }
 }
 
-pub fn part1() {
-    let input = stdin_to_vec::<String>();
-    assert_eq!(2, input.len());
+pub fn get_bus_offsets(bus_id_str: &str) -> Result<Vec<(usize, usize)>, ParseError> {
+    let mut bus_offsets: Vec<(usize, usize)> = vec![];
 
-    let earliest_depart_time = input[0].parse::<usize>().unwrap();
-    let bus_ids = input[1]
-        .split(',')
-        .filter(|id| *id != "x")
-        .map(|id| id.parse::<usize>().expect("Could not parse bus id"))
-        .collect::<Vec<usize>>();
+    for (i, bus_id) in bus_id_str.split(',').enumerate() {
+        if bus_id == "x" {
+            continue;
+        }
 
-    println!(
-        "Earliest depart time: {}, Bus ids: {:?}",
-        earliest_depart_time, bus_ids
-    );
+        let bus_id_num = bus_id
+            .parse::<usize>()
+            .ok()
+            .filter(|bus_id_num| *bus_id_num > 0)
+            .ok_or_else(|| ParseError::at(bus_id_str, bus_id, "a positive bus id or 'x'"))?;
 
-    let mut bus_departures = bus_ids
-        .iter()
-        .map(|bus_id| {
-            (
-                get_earliest_bus_departure_time(earliest_depart_time, *bus_id),
-                *bus_id,
-            )
-        })
-        .collect::<Vec<(usize, usize)>>();
+        bus_offsets.push((bus_id_num, i));
+    }
+
+    if bus_offsets.is_empty() {
+        return Err(ParseError::at(
+            bus_id_str,
+            bus_id_str,
+            "at least one bus id",
+        ));
+    }
 
-    bus_departures.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
-    println!("Earliest bus departures: {:?}", bus_departures);
+    Ok(bus_offsets)
+}
 
-    if let Some(earliest_bus_departure) = bus_departures.first() {
-        let minutes_to_wait = earliest_bus_departure.0 - earliest_depart_time;
-        let bus_id = earliest_bus_departure.1;
-        println!("Result: {}", minutes_to_wait * bus_id);
+fn residue(value: &BigUint, modulus: usize) -> usize {
+    usize::try_from(value % modulus).unwrap_or(0)
+}
+
+fn gcd(a: usize, b: usize) -> usize {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
     }
 }
 
-fn get_bus_offsets(bus_id_str: &str) -> Vec<(usize, usize)> {
-    let bus_id_strs = bus_id_str.split(',').collect::<Vec<&str>>();
+pub fn get_earliest_time(bus_id_offsets: &[(usize, usize)]) -> Option<BigUint> {
+    crate::trace!("{:?}", bus_id_offsets);
 
-    let mut bus_offsets: Vec<(usize, usize)> = vec![];
+    let mut time = BigUint::default();
+    let mut step = BigUint::from(1_u32);
 
-    for (i, bus_id_str) in bus_id_strs.iter().enumerate() {
-        if *bus_id_str == "x" {
-            continue;
+    for (bus_id, bus_time_offset) in bus_id_offsets {
+        let step_residue = residue(&step, *bus_id);
+        let mut time_residue = residue(&time, *bus_id);
+        let mut tries = 0;
+
+        while !(time_residue + bus_time_offset).is_multiple_of(*bus_id) {
+            if tries == *bus_id || cancel::is_cancelled() {
+                return None;
+            }
+
+            time += &step;
+            time_residue = (time_residue + step_residue) % bus_id;
+            tries += 1;
         }
 
-        let bus_id = bus_id_str.parse::<usize>().expect("Could not parse bus id");
-        bus_offsets.push((bus_id, i));
+        step *= bus_id / gcd(*bus_id, step_residue);
+        crate::trace!(
+            "Bus {} departs at offset {} from {}",
+            bus_id,
+            bus_time_offset,
+            time
+        );
+    }
+
+    if time == BigUint::default() {
+        time = step;
     }
 
-    bus_offsets
+    Some(time)
 }
 
-fn get_earliest_time(input: &str) -> usize {
-    let bus_id_offsets = get_bus_offsets(input);
-    println!("{:?}", bus_id_offsets);
-
-    let first_bus_id = bus_id_offsets.first().unwrap().0;
-    let mut first_bus_departure_time = first_bus_id;
-
-    loop {
-        let mut found_time = true;
-        for i in 1..bus_id_offsets.len() {
-            let bus_id = bus_id_offsets[i].0;
-            let bus_time_offset = bus_id_offsets[i].1;
-            
-            if (first_bus_departure_time + bus_time_offset) % bus_id != 0 {
-                found_time = false;
-                break;
-            }
-        }
+#[test]
+fn test_get_earliest_time() {
+    let earliest_time =
+        |input| get_earliest_time(&get_bus_offsets(input).unwrap()).map(|time| time.to_string());
 
-        if found_time {
-            return first_bus_departure_time;
+    assert_eq!(
+        earliest_time("7,13,x,x,59,x,31,19"),
+        Some("1068781".to_string())
+    );
+    assert_eq!(earliest_time("17,x,13,19"), Some("3417".to_string()));
+    assert_eq!(earliest_time("67,7,59,61"), Some("754018".to_string()));
+    assert_eq!(earliest_time("67,x,7,59,61"), Some("779210".to_string()));
+    assert_eq!(earliest_time("67,7,x,59,61"), Some("1261476".to_string()));
+    assert_eq!(
+        earliest_time("1789,37,47,1889"),
+        Some("1202161486".to_string())
+    );
+    assert_eq!(earliest_time("2,4"), None);
+}
+
+#[test]
+fn test_get_earliest_time_cancelled() {
+    let token = cancel::CancelToken::with_timeout(std::time::Duration::from_millis(50));
+    token.start_timer();
+
+    assert_eq!(token.scope(|| get_earliest_time(&[(2, 0), (4, 1)])), None);
+}
+
+#[test]
+fn test_get_bus_offsets_errors() {
+    let error = get_bus_offsets("7,13,y,19").unwrap_err();
+    assert_eq!(error.column, 6);
+    assert_eq!(error.text, "y");
+
+    let error = get_bus_offsets("7,0").unwrap_err();
+    assert_eq!(error.column, 3);
+}
+
+pub struct Day13 {
+    earliest_depart_time: usize,
+    bus_id_offsets: Vec<(usize, usize)>,
+}
+
+impl Solver for Day13 {
+    fn parse(input: &Input) -> Result<Self, ParseError> {
+        let mut lines = input.text().lines();
+
+        let earliest_depart_time_str = lines.next().unwrap_or("");
+        let earliest_depart_time = earliest_depart_time_str.parse::<usize>().map_err(|_| {
+            ParseError::new(1, earliest_depart_time_str, "an earliest departure time")
+                .with_line(1, earliest_depart_time_str)
+        })?;
+
+        let bus_id_str = lines.next().unwrap_or("");
+        let bus_id_offsets =
+            get_bus_offsets(bus_id_str).map_err(|err| err.with_line(2, bus_id_str))?;
+
+        if let Some(line) = lines.next() {
+            return Err(ParseError::new(1, line, "end of input").with_line(3, line));
         }
 
-        first_bus_departure_time += first_bus_id;
+        Ok(Self {
+            earliest_depart_time,
+            bus_id_offsets,
+        })
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> Option<String> {
+        let primes = (2..1000usize)
+            .filter(|n| (2..*n).take_while(|d| d * d <= *n).all(|d| n % d != 0))
+            .collect::<Vec<usize>>();
+        let mut bus_ids = generate::distinct(rng, primes, size).into_iter();
+
+        let bus_id_str = (0..size.max(1))
+            .map(
+                |i| match (i == 0 || generate::weighted(rng, 0.3), bus_ids.next()) {
+                    (true, Some(bus_id)) => bus_id.to_string(),
+                    _ => "x".to_string(),
+                },
+            )
+            .collect::<Vec<String>>()
+            .join(",");
+
+        Some(format!("{}\n{}\n", rng.gen_range(1..1_000_000), bus_id_str))
+    }
+
+    fn part1(&self) -> Result<Option<Answer>, SolveError> {
+        let earliest_depart_time = self.earliest_depart_time;
+        let bus_ids = self
+            .bus_id_offsets
+            .iter()
+            .map(|(bus_id, _)| *bus_id)
+            .collect::<Vec<usize>>();
+
+        crate::debug!(
+            "Earliest depart time: {}, Bus ids: {:?}",
+            earliest_depart_time,
+            bus_ids
+        );
+
+        let mut bus_departures = bus_ids
+            .iter()
+            .map(|bus_id| {
+                (
+                    get_earliest_bus_departure_time(earliest_depart_time, *bus_id),
+                    *bus_id,
+                )
+            })
+            .collect::<Vec<(usize, usize)>>();
+
+        bus_departures.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+        crate::debug!("Earliest bus departures: {:?}", bus_departures);
+
+        Ok(bus_departures.first().map(|(departure_time, bus_id)| {
+            let minutes_to_wait = departure_time - earliest_depart_time;
+            Answer::from(minutes_to_wait * bus_id)
+        }))
+    }
+
+    fn part2(&self) -> Result<Option<Answer>, SolveError> {
+        Ok(get_earliest_time(&self.bus_id_offsets).map(Answer::from))
     }
 }
 
-#[test]
-fn test_get_earliest_time() {
-    assert_eq!(get_earliest_time("7,13,x,x,59,x,31,19"), 1068781);
-    assert_eq!(get_earliest_time("17,x,13,19"), 3417);
-    assert_eq!(get_earliest_time("67,7,59,61"), 754018);
-    assert_eq!(get_earliest_time("67,x,7,59,61"), 779210);
-    assert_eq!(get_earliest_time("67,7,x,59,61"), 1261476);
-    assert_eq!(get_earliest_time("1789,37,47,1889"), 1202161486);
+pub fn register(registry: &mut Registry) {
+    registry.register::<Day13>(13);
 }
 
-pub fn part2() {
-    let input = stdin_to_vec::<String>();
-    assert_eq!(2, input.len());
-    println!("Earliest timestamp: {}", get_earliest_time(&input[1]));
+#[test]
+fn test_examples() {
+    let example = "939\n7,13,x,x,59,x,31,19\n";
+
+    assert_eq!(crate::runner::run_example(13, 1, example, &[]), "295");
+    assert_eq!(crate::runner::run_example(13, 2, example, &[]), "1068781");
 }
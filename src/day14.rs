@@ -1,9 +1,21 @@
-use crate::utils::{self, stdin_to_vec};
+use crate::{
+    answer::Answer,
+    cancel,
+    error::{ParseError, SolveError},
+    generate::{self, Rng},
+    input::Input,
+    solver::{Registry, Solver},
+    utils,
+};
+use rand::{seq::SliceRandom, Rng as _};
 use regex::Regex;
-use std::{collections::HashMap, str::FromStr, string::ParseError, vec};
+use std::{collections::HashMap, str::FromStr, sync::OnceLock};
+
+static MEM_REGEX: OnceLock<Regex> = OnceLock::new();
+static MASK_REGEX: OnceLock<Regex> = OnceLock::new();
 
 #[derive(Clone, Debug)]
-enum Instruction {
+pub enum Instruction {
     Mask(String),
     Mem(u64, u64),
 }
@@ -12,36 +24,49 @@ impl FromStr for Instruction {
     type Err = ParseError;
 
     fn from_str(input_str: &str) -> Result<Self, Self::Err> {
-        let mem_regex = Regex::new(r"mem\[(?P<address>\d+)\] = (?P<value>\d+)").unwrap();
-        let mask_regex = Regex::new(r"mask = (?P<mask>[01X]{36})").unwrap();
+        let mem_regex = MEM_REGEX
+            .get_or_init(|| Regex::new(r"^mem\[(?P<address>\d+)\] = (?P<value>\d+)$").unwrap());
+        let mask_regex =
+            MASK_REGEX.get_or_init(|| Regex::new(r"^mask = (?P<mask>[01X]{36})$").unwrap());
+
+        let parse_u64 = |capture: regex::Match, expected| {
+            capture
+                .as_str()
+                .parse::<u64>()
+                .map_err(|_| ParseError::at(input_str, capture.as_str(), expected))
+        };
 
         if let Some(captures) = mem_regex.captures(input_str) {
-            return Ok(Instruction::Mem(
-                captures["address"].parse::<u64>().unwrap(),
-                captures["value"].parse::<u64>().unwrap(),
-            ));
+            Ok(Instruction::Mem(
+                parse_u64(captures.name("address").unwrap(), "a 64-bit address")?,
+                parse_u64(captures.name("value").unwrap(), "a 64-bit value")?,
+            ))
         } else if let Some(captures) = mask_regex.captures(input_str) {
-            return Ok(Instruction::Mask(captures["mask"].to_string()));
+            Ok(Instruction::Mask(captures["mask"].to_string()))
         } else {
-            panic!("Unsupported input: {}", input_str);
+            Err(ParseError::at(
+                input_str,
+                input_str,
+                "'mask = <36 x [01X]>' or 'mem[<address>] = <value>'",
+            ))
         }
     }
 }
 
-struct Program {
+pub struct Program {
     instructions: Vec<Instruction>,
     mem: HashMap<u64, u64>,
 }
 
 impl Program {
-    fn new(instructions: &Vec<Instruction>) -> Self {
+    pub fn new(instructions: &[Instruction]) -> Self {
         Self {
-            instructions: instructions.clone(),
+            instructions: instructions.to_vec(),
             mem: HashMap::new(),
         }
     }
 
-    fn apply_mask_part1(&self, value: u64, mask: &str) -> u64 {
+    pub fn apply_mask_part1(&self, value: u64, mask: &str) -> u64 {
         let mut masked_value = value;
 
         for (bit_pos, mask_bit) in mask.chars().rev().enumerate() {
@@ -53,7 +78,7 @@ impl Program {
         masked_value
     }
 
-    fn run_part1(&mut self) -> u64 {
+    pub fn run_part1(&mut self) -> u64 {
         let mut current_mask = "";
 
         for instruction in &self.instructions {
@@ -69,70 +94,229 @@ impl Program {
         self.mem.values().sum::<u64>()
     }
 
-    fn apply_mask_part2(&self, address: u64, mask: &str) -> Vec<u64> {
-        let mut masked_address = address;
-        let mut x_bit_positions: Vec<u8> = vec![];
+    pub fn run_part2(&mut self) -> Option<u128> {
+        let mut current_mask = "";
+        let mut writes = vec![];
+
+        for instruction in &self.instructions {
+            match instruction {
+                Instruction::Mask(mask) => current_mask = mask,
+                Instruction::Mem(address, value) => {
+                    writes.push((FloatingAddress::new(*address, current_mask), *value));
+                }
+            }
+        }
+
+        let mut written: Vec<FloatingAddress> = vec![];
+        let mut sum = 0_u128;
+
+        for (address, value) in writes.into_iter().rev() {
+            let mut pieces = vec![address];
+
+            for other in &written {
+                if cancel::is_cancelled() {
+                    return None;
+                }
+
+                pieces = pieces
+                    .iter()
+                    .flat_map(|piece| piece.subtract(other))
+                    .collect();
+            }
+
+            sum += value as u128 * pieces.iter().map(FloatingAddress::count).sum::<u128>();
+            written.push(address);
+        }
+
+        Some(sum)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FloatingAddress {
+    pub fixed: u64,
+    pub floating: u64,
+}
+
+impl FloatingAddress {
+    pub fn new(address: u64, mask: &str) -> Self {
+        let mut fixed = address;
+        let mut floating = 0;
 
         for (bit_pos, mask_bit) in mask.chars().rev().enumerate() {
             if mask_bit == 'X' {
-                x_bit_positions.push(bit_pos as u8);
+                floating = utils::set_bit(floating, bit_pos as u8, true);
+                fixed = utils::set_bit(fixed, bit_pos as u8, false);
             } else if mask_bit == '1' {
-                masked_address = utils::set_bit(masked_address, bit_pos as u8, true);
+                fixed = utils::set_bit(fixed, bit_pos as u8, true);
             }
         }
 
-        let address_combination_count = 2_u16.pow(x_bit_positions.len() as u32);
-        let mut addresses = Vec::<u64>::with_capacity(address_combination_count as usize);
+        Self { fixed, floating }
+    }
 
-        for num in 0..address_combination_count {
-            let mut address = masked_address;
+    pub fn count(&self) -> u128 {
+        1_u128 << self.floating.count_ones()
+    }
 
-            for (bit_pos, x_bit_pos) in x_bit_positions.iter().enumerate() {
-                address = utils::set_bit(
-                    address,
-                    *x_bit_pos as u8,
-                    utils::check_bit(num, bit_pos as u8),
-                );
+    pub fn overlaps(&self, other: &Self) -> bool {
+        (self.fixed ^ other.fixed) & !(self.floating | other.floating) == 0
+    }
+
+    pub fn subtract(&self, other: &Self) -> Vec<Self> {
+        if !self.overlaps(other) {
+            return vec![*self];
+        }
+
+        let mut pieces = vec![];
+        let mut remaining = *self;
+        let split_bits = self.floating & !other.floating;
+
+        for bit_pos in 0..64 {
+            let bit = 1_u64 << bit_pos;
+            if split_bits & bit == 0 {
+                continue;
             }
 
-            addresses.push(address);
+            remaining.floating &= !bit;
+            pieces.push(Self {
+                fixed: (remaining.fixed & !bit) | (!other.fixed & bit),
+                floating: remaining.floating,
+            });
+            remaining.fixed = (remaining.fixed & !bit) | (other.fixed & bit);
         }
 
-        addresses
+        pieces
     }
+}
 
-    fn run_part2(&mut self) -> u64 {
-        let mut current_mask = "";
+pub const MAX_FLOATING_BITS: usize = 18;
 
-        for instruction in &self.instructions {
-            match instruction {
-                Instruction::Mask(mask) => current_mask = mask,
-                Instruction::Mem(address, value) => {
-                    for addr in &self.apply_mask_part2(*address, current_mask) {
-                        self.mem.insert(*addr, *value);
-                    }
-                }
+pub struct Day14 {
+    instructions: Vec<Instruction>,
+}
+
+impl Solver for Day14 {
+    fn parse(input: &Input) -> Result<Self, ParseError> {
+        let instructions = input.lines::<Instruction>()?;
+        crate::trace!("{:?}", instructions);
+
+        Ok(Self { instructions })
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> Option<String> {
+        let mut lines = vec![];
+
+        while lines.len() < size {
+            let floating_bits = rng.gen_range(0..=MAX_FLOATING_BITS);
+            let mut mask = generate::chars(rng, 36 - floating_bits, &['0', '1'])
+                .chars()
+                .chain((0..floating_bits).map(|_| 'X'))
+                .collect::<Vec<char>>();
+            mask.shuffle(rng);
+            lines.push(format!("mask = {}\n", mask.iter().collect::<String>()));
+
+            for _ in 0..rng.gen_range(1..=8) {
+                lines.push(format!(
+                    "mem[{}] = {}\n",
+                    rng.gen_range(0..65536),
+                    rng.gen_range(0..1u64 << 36)
+                ));
             }
         }
 
-        self.mem.values().sum::<u64>()
+        lines.truncate(size.max(1));
+        Some(lines.concat())
+    }
+
+    fn part1(&self) -> Result<Option<Answer>, SolveError> {
+        let mut program = Program::new(&self.instructions);
+
+        Ok(Some(Answer::from(program.run_part1())))
+    }
+
+    fn part2(&self) -> Result<Option<Answer>, SolveError> {
+        let mut program = Program::new(&self.instructions);
+
+        Ok(program.run_part2().map(Answer::from))
     }
 }
 
-pub fn part1() {
-    let instructions = stdin_to_vec::<Instruction>();
-    println!("{:?}", instructions);
+pub fn register(registry: &mut Registry) {
+    registry.register::<Day14>(14);
+}
 
-    let mut program = Program::new(&instructions);
+#[test]
+fn test_examples() {
+    let example = "mask = XXXXXXXXXXXXXXXXXXXXXXXXXXXXX1XXXX0X
+mem[8] = 11
+mem[7] = 101
+mem[8] = 0
+";
 
-    println!("Result: {:?}", program.run_part1());
+    let floating = "mask = 000000000000000000000000000000X1001X
+mem[42] = 100
+mask = 00000000000000000000000000000000X0XX
+mem[26] = 1
+";
+
+    assert_eq!(crate::runner::run_example(14, 1, example, &[]), "165");
+    assert_eq!(crate::runner::run_example(14, 2, floating, &[]), "208");
 }
 
-pub fn part2() {
-    let instructions = stdin_to_vec::<Instruction>();
-    println!("{:?}", instructions);
+#[test]
+fn test_many_floating_bits() {
+    let example = "mask = 00000000000000000000XXXXXXXXXXXXXXXX
+mem[0] = 1
+";
+
+    assert_eq!(crate::runner::run_example(14, 2, example, &[]), "65536");
+}
+
+#[test]
+fn test_all_floating_bits() {
+    let example = "mask = XXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXX
+mem[0] = 1
+mem[5] = 3
+";
+
+    assert_eq!(
+        crate::runner::run_example(14, 2, example, &[]),
+        (3_u64 << 36).to_string()
+    );
+}
 
-    let mut program = Program::new(&instructions);
+#[test]
+fn test_part2_matches_enumeration() {
+    let text = Day14::generate(&mut crate::generate::rng(14), 40).unwrap();
+    let instructions = text
+        .lines()
+        .map(|line| line.parse::<Instruction>().unwrap())
+        .collect::<Vec<Instruction>>();
+
+    let mut mem = HashMap::new();
+    let mut current_mask = "";
+
+    for instruction in &instructions {
+        match instruction {
+            Instruction::Mask(mask) => current_mask = mask,
+            Instruction::Mem(address, value) => {
+                let address = FloatingAddress::new(*address, current_mask);
+                let mut subset = 0_u64;
+
+                loop {
+                    mem.insert(address.fixed | subset, *value as u128);
+                    if subset == address.floating {
+                        break;
+                    }
+                    subset = subset.wrapping_sub(address.floating) & address.floating;
+                }
+            }
+        }
+    }
 
-    println!("Result: {:?}", program.run_part2());
+    assert_eq!(
+        Program::new(&instructions).run_part2(),
+        Some(mem.values().sum::<u128>())
+    );
 }
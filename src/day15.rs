@@ -1,8 +1,15 @@
 use std::collections::HashMap;
 
-use crate::utils::stdin_to_vec;
-
-struct Game {
+use crate::{
+    answer::Answer,
+    cancel,
+    error::{ParseError, SolveError},
+    generate::{self, Rng},
+    input::Input,
+    solver::{Param, Registry, Solver},
+};
+
+pub struct Game {
     turns: Vec<usize>,
     number_positions: HashMap<usize, usize>,
     number_spoken_count: HashMap<usize, usize>,
@@ -10,7 +17,7 @@ struct Game {
 
 impl Game {
     pub fn new(starting_numbers: Vec<usize>) -> Self {
-        println!("Starting numbers: {:?}", &starting_numbers);
+        crate::debug!("Starting numbers: {:?}", &starting_numbers);
 
         let mut number_positions = HashMap::<usize, usize>::new();
         let mut number_spoken_count = HashMap::<usize, usize>::new();
@@ -18,7 +25,7 @@ impl Game {
         for (num_turn, num) in starting_numbers.iter().enumerate() {
             number_positions.insert(*num, num_turn);
             number_spoken_count.insert(*num, 0);
-        }        
+        }
 
         Self {
             turns: starting_numbers,
@@ -60,30 +67,109 @@ impl Game {
     }
 }
 
-pub fn get_starting_numbers() -> Vec<usize> {
-    let input = stdin_to_vec::<String>();
-    assert_eq!(input.len(), 1);
+pub fn get_starting_numbers(input: &str) -> Result<Vec<usize>, ParseError> {
+    let mut lines = input.lines();
+    let line = lines.next().unwrap_or("");
+
+    if let Some(extra_line) = lines.next() {
+        return Err(ParseError::new(1, extra_line, "end of input").with_line(2, extra_line));
+    }
+
+    line.split(',')
+        .map(|s| {
+            s.parse::<usize>()
+                .map_err(|_| ParseError::at(line, s, "a starting number").with_line(1, line))
+        })
+        .collect::<Result<Vec<usize>, ParseError>>()
+}
+
+pub const PART1_TURNS: usize = 2020;
+pub const PART2_TURNS: usize = 30000000;
 
-    input[0]
-        .split(',')
-        .map(|s| s.parse().unwrap())
-        .collect::<Vec<usize>>()
+pub struct Day15 {
+    starting_numbers: Vec<usize>,
+    part1_turns: usize,
+    part2_turns: usize,
 }
 
-pub fn part1() {
-    let mut game = Game::new(get_starting_numbers());
+impl Solver for Day15 {
+    fn params() -> Vec<Param> {
+        vec![
+            Param::new("part1_turns", PART1_TURNS),
+            Param::new("part2_turns", PART2_TURNS),
+        ]
+    }
+
+    fn parse(input: &Input) -> Result<Self, ParseError> {
+        Ok(Self {
+            starting_numbers: get_starting_numbers(input.text())?,
+            part1_turns: input.param("part1_turns", PART1_TURNS)?,
+            part2_turns: input.param("part2_turns", PART2_TURNS)?,
+        })
+    }
 
-    while game.turn_count() < 2020 {
-        println!("Turn number: {}", game.next_turn());
+    fn generate(rng: &mut Rng, size: usize) -> Option<String> {
+        let starting_numbers = generate::distinct(rng, (0..size.max(1) * 2).collect(), size.max(1));
+
+        Some(format!(
+            "{}\n",
+            starting_numbers
+                .iter()
+                .map(usize::to_string)
+                .collect::<Vec<String>>()
+                .join(",")
+        ))
+    }
+
+    fn part1(&self) -> Result<Option<Answer>, SolveError> {
+        let mut game = Game::new(self.starting_numbers.clone());
+
+        while game.turn_count() < self.part1_turns {
+            if cancel::is_cancelled() {
+                return Ok(None);
+            }
+
+            let number = game.next_turn();
+            crate::trace!("Turn number: {}", number);
+        }
+
+        Ok(Some(Answer::from(game.get_last_turn_number())))
+    }
+
+    fn part2(&self) -> Result<Option<Answer>, SolveError> {
+        let mut game = Game::new(self.starting_numbers.clone());
+
+        while game.turn_count() < self.part2_turns {
+            if cancel::is_cancelled() {
+                return Ok(None);
+            }
+
+            game.next_turn();
+        }
+
+        Ok(Some(Answer::from(game.get_last_turn_number())))
     }
 }
 
-pub fn part2() {
-    let mut game = Game::new(get_starting_numbers());
+pub fn register(registry: &mut Registry) {
+    registry.register::<Day15>(15);
+}
 
-    while game.turn_count() < 30000000 {
-        game.next_turn();
+#[test]
+fn test_examples() {
+    let examples = [
+        ("0,3,6", "436"),
+        ("1,3,2", "1"),
+        ("2,1,3", "10"),
+        ("1,2,3", "27"),
+        ("2,3,1", "78"),
+        ("3,2,1", "438"),
+        ("3,1,2", "1836"),
+    ];
+
+    for (example, answer) in examples.iter() {
+        assert_eq!(crate::runner::run_example(15, 1, example, &[]), *answer);
     }
 
-    println!("Last turn number: {}", game.get_last_turn_number());
+    assert_eq!(crate::runner::run_example(15, 2, "0,3,6", &[]), "175594");
 }
@@ -1,10 +1,18 @@
-use crate::utils;
-use std::{fmt::Debug, str::FromStr, string::ParseError};
-use utils::stdin_to_vec;
+use crate::{
+    answer::Answer,
+    error::{ParseError, SolveError},
+    generate::{self, Rng},
+    input::Input,
+    solver::{Param, Registry, Solver},
+};
+use rand::{seq::SliceRandom, Rng as _};
+use std::{collections::BTreeMap, fmt::Debug, fs, str::FromStr};
+
+const PASSWORD_SHAPE: &str = "'<number>-<number> <letter>: <password>'";
 
 pub struct Password {
     pub policy_number1: usize,
-    pub plolicy_number2: usize,
+    pub policy_number2: usize,
     pub policy_letter: char,
     pub password: String,
 }
@@ -13,27 +21,41 @@ impl FromStr for Password {
     type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut chars = s.chars();
+        let (policy, password) = s
+            .split_once(':')
+            .ok_or_else(|| ParseError::at(s, s, PASSWORD_SHAPE))?;
+
+        let password = password
+            .strip_prefix(' ')
+            .ok_or_else(|| ParseError::at(s, password, "' ' after ':'"))?;
 
-        let minus_pos = chars.position(|c| c == '-').unwrap();
-        let policy_number1 = String::from(&s[..minus_pos]).parse::<usize>().unwrap();
+        let (policy_numbers, policy_letter) = policy
+            .split_once(' ')
+            .ok_or_else(|| ParseError::at(s, policy, PASSWORD_SHAPE))?;
 
-        chars = s.chars();
-        let space_pos = chars.position(|c| c == ' ').unwrap();
-        let plolicy_number2 = String::from(&s[minus_pos + 1..space_pos])
+        let (number1, number2) = policy_numbers
+            .split_once('-')
+            .ok_or_else(|| ParseError::at(s, policy_numbers, "'<number>-<number>'"))?;
+
+        let policy_number1 = number1
             .parse::<usize>()
-            .unwrap();
+            .map_err(|_| ParseError::at(s, number1, "a number"))?;
 
-        let policy_letter = chars.next().unwrap();
+        let policy_number2 = number2
+            .parse::<usize>()
+            .map_err(|_| ParseError::at(s, number2, "a number"))?;
 
-        assert_eq!(Some(':'), chars.next());
-        assert_eq!(Some(' '), chars.next());
+        let mut policy_letter_chars = policy_letter.chars();
+        let policy_letter = match (policy_letter_chars.next(), policy_letter_chars.next()) {
+            (Some(c), None) => c,
+            _ => return Err(ParseError::at(s, policy_letter, "a single letter")),
+        };
 
-        let password = chars.collect();
+        let password = password.to_string();
 
         Ok(Self {
             policy_number1,
-            plolicy_number2,
+            policy_number2,
             policy_letter,
             password,
         })
@@ -45,58 +67,502 @@ impl Debug for Password {
         write!(
             f,
             "(policy_number1={}, policy_number2={}, policy_letter={}, password={})",
-            self.policy_number1, self.plolicy_number2, self.policy_letter, self.password
+            self.policy_number1, self.policy_number2, self.policy_letter, self.password
         )
     }
 }
 
-fn is_password_valid_part1(p: &Password) -> bool {
-    let mut letter_count = 0;
-    for c in p.password.chars() {
-        if c == p.policy_letter {
-            letter_count += 1;
+pub trait PasswordPolicy {
+    fn is_valid(&self, p: &Password) -> bool;
+}
+
+pub struct LetterCountPolicy;
+
+impl PasswordPolicy for LetterCountPolicy {
+    fn is_valid(&self, p: &Password) -> bool {
+        let mut letter_count = 0;
+        for c in p.password.chars() {
+            if c == p.policy_letter {
+                letter_count += 1;
+            }
+        }
+
+        letter_count >= p.policy_number1 && letter_count <= p.policy_number2
+    }
+}
+
+pub struct LetterPositionPolicy;
+
+impl PasswordPolicy for LetterPositionPolicy {
+    fn is_valid(&self, p: &Password) -> bool {
+        let letter_at = |position: usize| {
+            position
+                .checked_sub(1)
+                .and_then(|index| p.password.chars().nth(index))
+        };
+
+        match (letter_at(p.policy_number1), letter_at(p.policy_number2)) {
+            (Some(letter1), Some(letter2)) => {
+                (letter1 == p.policy_letter || letter2 == p.policy_letter) && letter1 != letter2
+            }
+            _ => {
+                crate::debug!("Position out of range: {:?}", p);
+                false
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CharClass {
+    Lower,
+    Upper,
+    Letter,
+    Digit,
+    Symbol,
+}
+
+impl CharClass {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "lower" => Some(CharClass::Lower),
+            "upper" => Some(CharClass::Upper),
+            "letter" => Some(CharClass::Letter),
+            "digit" => Some(CharClass::Digit),
+            "symbol" => Some(CharClass::Symbol),
+            _ => None,
+        }
+    }
+
+    pub fn contains(self, c: char) -> bool {
+        match self {
+            CharClass::Lower => c.is_lowercase(),
+            CharClass::Upper => c.is_uppercase(),
+            CharClass::Letter => c.is_alphabetic(),
+            CharClass::Digit => c.is_numeric(),
+            CharClass::Symbol => !c.is_alphanumeric() && !c.is_whitespace(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Policy {
+    LetterCount,
+    LetterPosition,
+    MinLength(usize),
+    MaxLength(usize),
+    Requires(CharClass),
+    MaxRepeat(usize),
+    Not(Box<Policy>),
+    And(Vec<Policy>),
+    Or(Vec<Policy>),
+}
+
+impl PasswordPolicy for Policy {
+    fn is_valid(&self, p: &Password) -> bool {
+        match self {
+            Policy::LetterCount => LetterCountPolicy.is_valid(p),
+            Policy::LetterPosition => LetterPositionPolicy.is_valid(p),
+            Policy::MinLength(len) => p.password.chars().count() >= *len,
+            Policy::MaxLength(len) => p.password.chars().count() <= *len,
+            Policy::Requires(class) => p.password.chars().any(|c| class.contains(c)),
+            Policy::MaxRepeat(max_run) => {
+                let mut run: Option<(char, usize)> = None;
+
+                p.password.chars().all(|c| {
+                    let run_len = match run {
+                        Some((run_char, run_len)) if run_char == c => run_len + 1,
+                        _ => 1,
+                    };
+                    run = Some((c, run_len));
+
+                    run_len <= *max_run
+                })
+            }
+            Policy::Not(policy) => !policy.is_valid(p),
+            Policy::And(policies) => policies.iter().all(|policy| policy.is_valid(p)),
+            Policy::Or(policies) => policies.iter().any(|policy| policy.is_valid(p)),
+        }
+    }
+}
+
+const POLICY_KEYWORDS: [&str; 9] = [
+    "and",
+    "or",
+    "not",
+    "count",
+    "position",
+    "min_length",
+    "max_length",
+    "requires",
+    "max_repeat",
+];
+
+fn policy_tokens(expr: &str) -> Vec<&str> {
+    let mut tokens = vec![];
+    let mut word_start = None;
+
+    for (i, c) in expr.char_indices() {
+        if c.is_whitespace() || c == '(' || c == ')' {
+            if let Some(start) = word_start.take() {
+                tokens.push(&expr[start..i]);
+            }
+            if !c.is_whitespace() {
+                tokens.push(&expr[i..i + 1]);
+            }
+        } else if word_start.is_none() {
+            word_start = Some(i);
         }
     }
 
-    letter_count >= p.policy_number1 && letter_count <= p.plolicy_number2
+    if let Some(start) = word_start {
+        tokens.push(&expr[start..]);
+    }
+
+    tokens
+}
+
+struct PolicyParser<'a> {
+    source: &'a str,
+    tokens: Vec<&'a str>,
+    pos: usize,
+    names: &'a BTreeMap<String, Policy>,
 }
 
-pub fn part1() {
-    let passwords: Vec<Password> = stdin_to_vec::<Password>();
+impl<'a> PolicyParser<'a> {
+    fn peek(&self) -> Option<&'a str> {
+        self.tokens.get(self.pos).copied()
+    }
+
+    fn next_token(&mut self, expected: &str) -> Result<&'a str, ParseError> {
+        let token = self.peek().ok_or_else(|| {
+            ParseError::at(self.source, &self.source[self.source.len()..], expected)
+        })?;
+        self.pos += 1;
+
+        Ok(token)
+    }
+
+    fn number(&mut self) -> Result<usize, ParseError> {
+        let token = self.next_token("a number")?;
+
+        token
+            .parse::<usize>()
+            .map_err(|_| ParseError::at(self.source, token, "a number"))
+    }
+
+    fn or(&mut self) -> Result<Policy, ParseError> {
+        let mut policies = vec![self.and()?];
+
+        while self.peek() == Some("or") {
+            self.pos += 1;
+            policies.push(self.and()?);
+        }
+
+        Ok(match policies.len() {
+            1 => policies.remove(0),
+            _ => Policy::Or(policies),
+        })
+    }
+
+    fn and(&mut self) -> Result<Policy, ParseError> {
+        let mut policies = vec![self.unary()?];
+
+        while self.peek() == Some("and") {
+            self.pos += 1;
+            policies.push(self.unary()?);
+        }
+
+        Ok(match policies.len() {
+            1 => policies.remove(0),
+            _ => Policy::And(policies),
+        })
+    }
+
+    fn unary(&mut self) -> Result<Policy, ParseError> {
+        let token = self.next_token("a policy")?;
 
-    let mut valid_password_count = 0;
-    for password in &passwords {
-        if is_password_valid_part1(password) {
-            println!("Password is valid: {:?}", password);
-            valid_password_count += 1;
-        } else {
-            println!("Password is NOT valid: {:?}", password);
+        match token {
+            "not" => Ok(Policy::Not(Box::new(self.unary()?))),
+            "(" => {
+                let policy = self.or()?;
+                match self.next_token("')'")? {
+                    ")" => Ok(policy),
+                    token => Err(ParseError::at(self.source, token, "')'")),
+                }
+            }
+            "count" => Ok(Policy::LetterCount),
+            "position" => Ok(Policy::LetterPosition),
+            "min_length" => Ok(Policy::MinLength(self.number()?)),
+            "max_length" => Ok(Policy::MaxLength(self.number()?)),
+            "max_repeat" => Ok(Policy::MaxRepeat(self.number()?)),
+            "requires" => {
+                let class = self.next_token("a character class")?;
+                CharClass::from_name(class)
+                    .map(Policy::Requires)
+                    .ok_or_else(|| {
+                        ParseError::at(
+                            self.source,
+                            class,
+                            "'lower', 'upper', 'letter', 'digit' or 'symbol'",
+                        )
+                    })
+            }
+            name => self.names.get(name).cloned().ok_or_else(|| {
+                ParseError::at(self.source, name, "a policy or a defined policy name")
+            }),
         }
     }
+}
+
+fn parse_policy(
+    source: &str,
+    expr: &str,
+    names: &BTreeMap<String, Policy>,
+) -> Result<Policy, ParseError> {
+    let mut parser = PolicyParser {
+        source,
+        tokens: policy_tokens(expr),
+        pos: 0,
+        names,
+    };
 
-    println!("Valid password count: {}", valid_password_count);
+    let policy = parser.or()?;
+
+    match parser.peek() {
+        Some(token) => Err(ParseError::at(
+            source,
+            token,
+            "'and', 'or' or end of policy",
+        )),
+        None => Ok(policy),
+    }
 }
 
-fn is_password_valid_part2(p: &Password) -> bool {
-    let password_letters: Vec<char> = p.password.chars().collect();
-    let letter1: char = password_letters[p.policy_number1 - 1];
-    let letter2: char = password_letters[p.plolicy_number2 - 1];
+impl Policy {
+    pub fn parse(expr: &str, names: &BTreeMap<String, Policy>) -> Result<Self, ParseError> {
+        parse_policy(expr, expr, names)
+    }
+}
+
+pub fn parse_policy_file(text: &str) -> Result<BTreeMap<String, Policy>, ParseError> {
+    let mut names = BTreeMap::new();
+
+    for (line_index, line) in text.lines().enumerate() {
+        let content = line.split('#').next().unwrap_or("").trim();
+        if content.is_empty() {
+            continue;
+        }
+
+        let (name, expr) = content.split_once('=').ok_or_else(|| {
+            ParseError::at(line, content, "'<name> = <policy>'").with_line(line_index + 1, line)
+        })?;
+
+        let name = name.trim();
+        let is_identifier =
+            !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
 
-    (letter1 == p.policy_letter || letter2 == p.policy_letter) && letter1 != letter2
+        if !is_identifier || POLICY_KEYWORDS.contains(&name) {
+            return Err(
+                ParseError::at(line, name, "a policy name that is not a keyword")
+                    .with_line(line_index + 1, line),
+            );
+        }
+
+        let policy =
+            parse_policy(line, expr, &names).map_err(|err| err.with_line(line_index + 1, line))?;
+        names.insert(name.to_string(), policy);
+    }
+
+    Ok(names)
 }
 
-pub fn part2() {
-    let passwords: Vec<Password> = utils::stdin_to_vec::<Password>();
+pub const PART1_POLICY: &str = "count";
+pub const PART2_POLICY: &str = "position";
 
-    let mut valid_password_count = 0;
-    for password in &passwords {
-        if is_password_valid_part2(password) {
-            println!("Password is valid: {:?}", password);
-            valid_password_count += 1;
-        } else {
-            println!("Password is NOT valid: {:?}", password);
+pub struct Day2 {
+    passwords: Vec<Password>,
+    part1_policy: Policy,
+    part2_policy: Policy,
+}
+
+impl Day2 {
+    fn count_valid_passwords(&self, policy: &dyn PasswordPolicy) -> usize {
+        let mut valid_password_count = 0;
+        for password in &self.passwords {
+            if policy.is_valid(password) {
+                crate::trace!("Password is valid: {:?}", password);
+                valid_password_count += 1;
+            } else {
+                crate::trace!("Password is NOT valid: {:?}", password);
+            }
         }
+
+        valid_password_count
+    }
+}
+
+impl Solver for Day2 {
+    fn params() -> Vec<Param> {
+        vec![
+            Param::new("part1_policy", PART1_POLICY),
+            Param::new("part2_policy", PART2_POLICY),
+            Param::new("policy_file", ""),
+        ]
+    }
+
+    fn parse(input: &Input) -> Result<Self, ParseError> {
+        let policy_file = input.param("policy_file", String::new())?;
+        let names = match policy_file.as_str() {
+            "" => BTreeMap::new(),
+            path => {
+                let text = input.param_with("policy_file", String::new(), |path| {
+                    fs::read_to_string(path)
+                        .map_err(|_| ParseError::new(1, "", "a readable policy file"))
+                })?;
+
+                parse_policy_file(&text).map_err(|mut err| {
+                    err.expected = format!("{} in policy file '{}'", err.expected, path);
+                    err
+                })?
+            }
+        };
+
+        Ok(Self {
+            passwords: input.lines::<Password>()?,
+            part1_policy: input.param_with("part1_policy", Policy::LetterCount, |expr| {
+                Policy::parse(expr, &names)
+            })?,
+            part2_policy: input.param_with("part2_policy", Policy::LetterPosition, |expr| {
+                Policy::parse(expr, &names)
+            })?,
+        })
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> Option<String> {
+        let letters = ['a', 'b', 'c', 'd', 'e'];
+
+        Some(generate::lines(rng, size, |rng| {
+            let password_len = rng.gen_range(1..=20);
+            let password = generate::chars(rng, password_len, &letters);
+            let number1 = rng.gen_range(0..=password.len());
+            let number2 = rng.gen_range(number1..=password.len() + 1);
+            let letter = letters.choose(rng).unwrap_or(&'a');
+
+            format!("{}-{} {}: {}", number1, number2, letter, password)
+        }))
+    }
+
+    fn part1(&self) -> Result<Option<Answer>, SolveError> {
+        let valid_password_count = self.count_valid_passwords(&self.part1_policy);
+        Ok(Some(Answer::from(valid_password_count)))
     }
 
-    println!("Valid password count: {}", valid_password_count);
+    fn part2(&self) -> Result<Option<Answer>, SolveError> {
+        let valid_password_count = self.count_valid_passwords(&self.part2_policy);
+        Ok(Some(Answer::from(valid_password_count)))
+    }
+}
+
+pub fn register(registry: &mut Registry) {
+    registry.register::<Day2>(2);
+}
+
+#[test]
+fn test_examples() {
+    let example = "1-3 a: abcde\n1-3 b: cdefg\n2-9 c: ccccccccc\n";
+
+    assert_eq!(crate::runner::run_example(2, 1, example, &[]), "2");
+    assert_eq!(crate::runner::run_example(2, 2, example, &[]), "1");
+}
+
+#[test]
+fn test_policy() {
+    let password = |password: &str| Password {
+        policy_number1: 1,
+        policy_number2: 3,
+        policy_letter: 'a',
+        password: password.to_string(),
+    };
+    let names = BTreeMap::new();
+    let policy = |expr| Policy::parse(expr, &names).unwrap();
+
+    assert_eq!(
+        policy("min_length 8 and (requires digit or requires symbol) and not max_length 3"),
+        Policy::And(vec![
+            Policy::MinLength(8),
+            Policy::Or(vec![
+                Policy::Requires(CharClass::Digit),
+                Policy::Requires(CharClass::Symbol)
+            ]),
+            Policy::Not(Box::new(Policy::MaxLength(3))),
+        ])
+    );
+
+    let strong = policy("min_length 8 and requires upper and requires digit and max_repeat 2");
+    assert!(strong.is_valid(&password("Passw0rd")));
+    assert!(!strong.is_valid(&password("Passsw0rd")));
+    assert!(!strong.is_valid(&password("password1")));
+    assert!(policy("count and not position").is_valid(&password("aba")));
+
+    let err = Policy::parse("min_length 8 and requires vowel", &names).unwrap_err();
+    assert_eq!((err.column, err.text.as_str()), (27, "vowel"));
+    assert_eq!(Policy::parse("(count", &names).unwrap_err().expected, "')'");
+    assert_eq!(
+        Policy::parse("count position", &names).unwrap_err().column,
+        7
+    );
+}
+
+#[test]
+fn test_policy_file() {
+    let text = "# corporate rules
+long = min_length 12
+strong = long and requires upper   # shared with vpn
+";
+    let names = parse_policy_file(text).unwrap();
+    assert_eq!(
+        names["strong"],
+        Policy::And(vec![
+            Policy::MinLength(12),
+            Policy::Requires(CharClass::Upper)
+        ])
+    );
+
+    let err = parse_policy_file("long = min_length 12\nweak = short\n").unwrap_err();
+    assert_eq!((err.line, err.column), (Some(2), 8));
+    assert!(parse_policy_file("and = count\n").is_err());
+
+    let example = "1-3 a: abcde\n1-3 b: cdefg\n2-9 c: ccccccccc\n";
+    assert_eq!(
+        crate::runner::run_example(2, 1, example, &[("part1_policy", "not count")]),
+        "1"
+    );
+    assert!(
+        crate::runner::run_example(2, 1, example, &[("part1_policy", "bogus")])
+            .contains("for parameter 'part1_policy'")
+    );
+}
+
+#[test]
+fn test_parse_errors() {
+    let column = |line: &str| {
+        let err = line.parse::<Password>().unwrap_err();
+        (err.column, err.text, err.expected)
+    };
+
+    assert_eq!(column("1-3 a:abcde").0, 7);
+    assert_eq!(column("1-3 a:abcde").2, "' ' after ':'");
+    assert_eq!(column("1-x a: abcde").0, 3);
+    assert_eq!(column("13 a: abcde").1, "13");
+    assert_eq!(column("1-3 ab: abcde").0, 5);
+    assert_eq!(column("1-3: abcde").0, 1);
+    assert_eq!(column("1-3 a abcde").2, PASSWORD_SHAPE);
+}
+
+#[test]
+fn test_positions_out_of_range() {
+    let example = "0-1 a: abcde\n1-9 a: abcde\n1-1 a: a\n2-3 b: abc\n";
+
+    assert_eq!(crate::runner::run_example(2, 2, example, &[]), "1");
 }
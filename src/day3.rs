@@ -1,6 +1,15 @@
-use crate::utils;
-use std::{fmt::Debug, str::FromStr, string::ParseError};
-use utils::stdin_to_vec;
+use crate::{
+    answer::Answer,
+    error::{ParseError, SolveError},
+    generate::{self, Rng},
+    input::Input,
+    solver::{Param, Registry, Solver},
+    utils,
+};
+use std::{
+    fmt::{self, Debug},
+    str::FromStr,
+};
 
 pub struct SlopePattern {
     pub pattern: Vec<char>,
@@ -10,6 +19,14 @@ impl FromStr for SlopePattern {
     type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() {
+            return Err(ParseError::at(s, s, "a row of '.' or '#'"));
+        }
+
+        if let Some((pos, c)) = s.char_indices().find(|(_, c)| *c != '.' && *c != '#') {
+            return Err(ParseError::at(s, &s[pos..pos + c.len_utf8()], "'.' or '#'"));
+        }
+
         Ok(Self {
             pattern: s.chars().collect(),
         })
@@ -21,7 +38,7 @@ impl Debug for SlopePattern {
         let mut formatted_pattern = String::new();
 
         for c in &self.pattern {
-            formatted_pattern.push(c.clone());
+            formatted_pattern.push(*c);
         }
 
         write!(f, "{}", formatted_pattern)
@@ -36,20 +53,20 @@ impl Clone for SlopePattern {
     }
 }
 
-fn print_patterns(patterns: &Vec<SlopePattern>) {
-    println!("\nSlope patterns:");
+pub fn print_patterns(patterns: &[SlopePattern]) {
+    crate::trace!("\nSlope patterns:");
     for p in patterns {
-        println!("{:?}", p);
+        crate::trace!("{:?}", p);
     }
-    println!();
+    crate::trace!();
 }
 
-fn count_trees(
-    patterns_template: &Vec<SlopePattern>,
+pub fn count_trees(
+    patterns_template: &[SlopePattern],
     shift_right_by: usize,
     shift_down_by: usize,
 ) -> usize {
-    let mut patterns = patterns_template.clone();
+    let mut patterns = patterns_template.to_vec();
 
     let mut horizontal_pos: usize = shift_right_by;
     let mut vertical_pos = shift_down_by;
@@ -77,28 +94,166 @@ fn count_trees(
 
     print_patterns(&patterns);
 
-    println!(
+    crate::debug!(
         "Right: {}, Down: {}, Trees: {}",
-        shift_right_by, shift_down_by, tree_count
+        shift_right_by,
+        shift_down_by,
+        tree_count
     );
 
     tree_count
 }
 
-pub fn part1() {
-    let patterns_template: Vec<SlopePattern> = stdin_to_vec::<SlopePattern>();
-    count_trees(&patterns_template, 3, 1);
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Slopes(pub Vec<(usize, usize)>);
+
+impl FromStr for Slopes {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let slopes = s
+            .split_whitespace()
+            .map(|slope| {
+                let (right, down) = utils::parse_pair::<usize>(slope)
+                    .map_err(|err| ParseError::at(s, slope, &err.expected))?;
+
+                if down == 0 {
+                    return Err(ParseError::at(
+                        s,
+                        slope,
+                        "a slope moving down by at least 1",
+                    ));
+                }
+
+                Ok((right, down))
+            })
+            .collect::<Result<Vec<(usize, usize)>, ParseError>>()?;
+
+        if slopes.is_empty() {
+            return Err(ParseError::at(s, s, "at least one '<right>,<down>' slope"));
+        }
+
+        Ok(Slopes(slopes))
+    }
+}
+
+impl fmt::Display for Slopes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let slopes = self
+            .0
+            .iter()
+            .map(|(right, down)| format!("{},{}", right, down))
+            .collect::<Vec<String>>();
+
+        write!(f, "{}", slopes.join(" "))
+    }
+}
+
+impl Slopes {
+    fn count_trees_product(&self, patterns: &[SlopePattern]) -> usize {
+        let mut mult_result = 1;
+        for (right, down) in &self.0 {
+            let tree_count = count_trees(patterns, *right, *down);
+            mult_result *= tree_count;
+        }
+
+        mult_result
+    }
+}
+
+pub fn part1_slopes() -> Slopes {
+    Slopes(vec![(3, 1)])
 }
 
-pub fn part2() {
-    let patterns_template: Vec<SlopePattern> = stdin_to_vec::<SlopePattern>();
-    let traverse_rules = [(1, 1), (3, 1), (5, 1), (7, 1), (1, 2)];
+pub fn part2_slopes() -> Slopes {
+    Slopes(vec![(1, 1), (3, 1), (5, 1), (7, 1), (1, 2)])
+}
+
+pub struct Day3 {
+    patterns_template: Vec<SlopePattern>,
+    part1_slopes: Slopes,
+    part2_slopes: Slopes,
+}
+
+impl Solver for Day3 {
+    fn params() -> Vec<Param> {
+        vec![
+            Param::new("slope", part1_slopes()),
+            Param::new("slopes", part2_slopes()),
+        ]
+    }
+
+    fn parse(input: &Input) -> Result<Self, ParseError> {
+        Ok(Self {
+            patterns_template: input.lines::<SlopePattern>()?,
+            part1_slopes: input.param("slope", part1_slopes())?,
+            part2_slopes: input.param("slopes", part2_slopes())?,
+        })
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> Option<String> {
+        Some(generate::lines(rng, size, |rng| {
+            generate::chars(rng, 31, &['.', '.', '.', '#'])
+        }))
+    }
+
+    fn part1(&self) -> Result<Option<Answer>, SolveError> {
+        let tree_count = self
+            .part1_slopes
+            .count_trees_product(&self.patterns_template);
+        Ok(Some(Answer::from(tree_count)))
+    }
 
-    let mut mult_result = 1;
-    for rule in traverse_rules.iter() {
-        let tree_count = count_trees(&patterns_template, rule.0, rule.1);
-        mult_result *= tree_count;
+    fn part2(&self) -> Result<Option<Answer>, SolveError> {
+        let mult_result = self
+            .part2_slopes
+            .count_trees_product(&self.patterns_template);
+        Ok(Some(Answer::from(mult_result)))
     }
+}
+
+pub fn register(registry: &mut Registry) {
+    registry.register::<Day3>(3);
+}
+
+#[test]
+fn test_slopes() {
+    assert_eq!(
+        "3,1 1,2".parse::<Slopes>(),
+        Ok(Slopes(vec![(3, 1), (1, 2)]))
+    );
+    assert_eq!(
+        part2_slopes().to_string().parse::<Slopes>(),
+        Ok(part2_slopes())
+    );
+
+    let error = "3,1 1,0".parse::<Slopes>().unwrap_err();
+    assert_eq!((error.column, error.text.as_str()), (5, "1,0"));
+}
+
+#[test]
+fn test_examples() {
+    let example = "..##.......
+#...#...#..
+.#....#..#.
+..#.#...#.#
+.#...##..#.
+..#.##.....
+.#.#.#....#
+.#........#
+#.##...#...
+#...##....#
+.#..#...#.#
+";
+
+    assert_eq!(crate::runner::run_example(3, 1, example, &[]), "7");
+    assert_eq!(crate::runner::run_example(3, 2, example, &[]), "336");
+}
+
+#[test]
+fn test_empty_rows() {
+    let error = "".parse::<SlopePattern>().unwrap_err();
+    assert_eq!(error.expected, "a row of '.' or '#'");
 
-    println!("Multiplication result: {}", mult_result);
+    assert!(crate::runner::run_example(3, 1, "..#\n\n", &[]).starts_with("parse error"));
 }
@@ -1,11 +1,18 @@
-use io::stdin;
-use std::io::{self, Read};
-use std::{collections::HashMap, vec};
+use crate::{
+    answer::Answer,
+    error::{ParseError, SolveError},
+    generate::{self, Rng},
+    input::Input,
+    solver::{Registry, Solver},
+    utils,
+};
+use rand::{seq::SliceRandom, Rng as _};
+use std::collections::HashMap;
 
 #[derive(Debug)]
-struct Passport {
+pub struct Passport {
     required_field_keys: Vec<String>,
-    fields: HashMap<String, String>,
+    pub fields: HashMap<String, String>,
 }
 
 impl Passport {
@@ -41,7 +48,7 @@ impl Passport {
         }
 
         if let Ok(year) = value.parse::<u32>() {
-            year >= min && year <= max
+            (min..=max).contains(&year)
         } else {
             false
         }
@@ -50,13 +57,13 @@ impl Passport {
     fn is_valid_height(&self, value: &str) -> bool {
         if value.ends_with("cm") {
             if let Ok(height) = value.trim_end_matches("cm").parse::<u32>() {
-                return height >= 150 && height <= 193;
+                return (150..=193).contains(&height);
             } else {
                 return false;
             }
         } else if value.ends_with("in") {
             if let Ok(height) = value.trim_end_matches("in").parse::<u32>() {
-                return height >= 59 && height <= 76;
+                return (59..=76).contains(&height);
             } else {
                 return false;
             }
@@ -76,11 +83,11 @@ impl Passport {
                 return false;
             }
         }
-        return chars.all(|c| c.is_numeric() || c.is_ascii_hexdigit());
+        chars.all(|c| c.is_numeric() || c.is_ascii_hexdigit())
     }
 
     fn is_valid_eye_color(&self, value: &str) -> bool {
-        let eye_colors = vec!["amb", "blu", "brn", "gry", "grn", "hzl", "oth"];
+        let eye_colors = ["amb", "blu", "brn", "gry", "grn", "hzl", "oth"];
         eye_colors.contains(&value)
     }
 
@@ -89,7 +96,7 @@ impl Passport {
             return false;
         }
 
-        return value.chars().all(char::is_numeric);
+        value.chars().all(char::is_numeric)
     }
 
     pub fn is_valid_part2(&self) -> bool {
@@ -120,6 +127,12 @@ impl Passport {
     }
 }
 
+impl Default for Passport {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[test]
 fn test_is_valid_year() {
     let passport = Passport::new();
@@ -160,44 +173,159 @@ fn test_is_valid_passport_id() {
     assert!(!passport.is_valid_passport_id("0123456789"));
 }
 
-fn stdin_to_passports() -> Vec<Passport> {
-    let mut input_str = String::new();
-    let _ = stdin().read_to_string(&mut input_str);
+pub fn input_to_passports(input: &Input) -> Result<Vec<Passport>, ParseError> {
+    let mut passports: Vec<Passport> = vec![];
+
+    for group in utils::record_groups(input.text()) {
+        let mut passport = Passport::new();
+
+        for (record_line, field_str) in utils::record_tokens(&group) {
+            match field_str.split_once(':') {
+                Some((key, value)) => {
+                    passport.fields.insert(key.to_string(), value.to_string());
+                }
+                None => input.reject(
+                    ParseError::at(record_line.text, field_str, "'<key>:<value>'")
+                        .with_line(record_line.line_num, record_line.text),
+                )?,
+            }
+        }
 
-    let mut passports: Vec<Passport> = vec![Passport::new()];
+        passports.push(passport);
+    }
 
-    for field_str in input_str.split(|c| c == ' ' || c == '\n') {
-        if field_str.trim().is_empty() {
-            passports.push(Passport::new());
-            continue;
-        }
+    crate::trace!("{:?}", passports);
+    input.finish(passports)
+}
 
-        let mut field_split = field_str.split(':');
-        let key = field_split.next().unwrap();
-        let value = field_split.next().unwrap();
+pub struct Day4 {
+    passports: Vec<Passport>,
+}
 
-        let passport = passports.last_mut().unwrap();
-        passport.fields.insert(key.to_string(), value.to_string());
+impl Solver for Day4 {
+    fn parse(input: &Input) -> Result<Self, ParseError> {
+        Ok(Self {
+            passports: input_to_passports(input)?,
+        })
     }
 
-    println!("{:?}", passports);
-    passports
-}
+    fn generate(rng: &mut Rng, size: usize) -> Option<String> {
+        let eye_colors = ["amb", "blu", "brn", "gry", "grn", "hzl", "oth", "wat"];
+        let digits = ('0'..='9').collect::<Vec<char>>();
+        let hex_digits = ('0'..='9').chain('a'..='f').collect::<Vec<char>>();
+
+        let passports = (0..size)
+            .map(|_| {
+                let mut fields = vec![
+                    format!("byr:{}", rng.gen_range(1900..=2010)),
+                    format!("iyr:{}", rng.gen_range(2005..=2025)),
+                    format!("eyr:{}", rng.gen_range(2015..=2035)),
+                    match rng.gen_range(0..3) {
+                        0 => format!("hgt:{}cm", rng.gen_range(140..=200)),
+                        1 => format!("hgt:{}in", rng.gen_range(55..=80)),
+                        _ => format!("hgt:{}", rng.gen_range(50..=200)),
+                    },
+                    match generate::weighted(rng, 0.8) {
+                        true => format!("hcl:#{}", generate::chars(rng, 6, &hex_digits)),
+                        false => format!("hcl:{}", generate::chars(rng, 6, &['z', 'x', '1'])),
+                    },
+                    format!("ecl:{}", eye_colors.choose(rng).unwrap_or(&"oth")),
+                    format!("cid:{}", rng.gen_range(100..=350)),
+                ];
+
+                let pid_len = rng.gen_range(8..=10);
+                fields.push(format!("pid:{}", generate::chars(rng, pid_len, &digits)));
+                fields.retain(|_| generate::weighted(rng, 0.9));
+                fields.shuffle(rng);
+
+                fields
+                    .iter()
+                    .map(|field| match generate::weighted(rng, 0.7) {
+                        true => format!("{} ", field),
+                        false => format!("{}\n", field),
+                    })
+                    .collect::<String>()
+                    .trim_end()
+                    .to_string()
+                    + "\n"
+            })
+            .collect::<Vec<String>>();
+
+        Some(passports.join("\n"))
+    }
 
-pub fn part1() {
-    let valid_passport_count = stdin_to_passports()
-        .iter()
-        .filter(|passport| passport.is_valid_part1())
-        .count();
+    fn part1(&self) -> Result<Option<Answer>, SolveError> {
+        let valid_passport_count = self
+            .passports
+            .iter()
+            .filter(|passport| passport.is_valid_part1())
+            .count();
 
-    println!("Valid passport count: {}", valid_passport_count);
+        Ok(Some(Answer::from(valid_passport_count)))
+    }
+
+    fn part2(&self) -> Result<Option<Answer>, SolveError> {
+        let valid_passport_count = self
+            .passports
+            .iter()
+            .filter(|passport| passport.is_valid_part2())
+            .count();
+
+        Ok(Some(Answer::from(valid_passport_count)))
+    }
 }
 
-pub fn part2() {
-    let valid_passport_count = stdin_to_passports()
-        .iter()
-        .filter(|passport| passport.is_valid_part2())
-        .count();
+pub fn register(registry: &mut Registry) {
+    registry.register::<Day4>(4);
+}
+
+#[test]
+fn test_examples() {
+    let example = "ecl:gry pid:860033327 eyr:2020 hcl:#fffffd
+byr:1937 iyr:2017 cid:147 hgt:183cm
+
+iyr:2013 ecl:amb cid:350 eyr:2023 pid:028048884
+hcl:#cfa07d byr:1929
+
+hcl:#ae17e1 iyr:2013
+eyr:2024
+ecl:brn pid:760753108 byr:1931
+hgt:179cm
+
+hcl:#cfa07d eyr:2025 pid:166559648
+iyr:2011 ecl:brn hgt:59in
+";
+
+    let invalid = "eyr:1972 cid:100
+hcl:#18171d ecl:amb hgt:170 pid:186cm iyr:2018 byr:1926
+
+iyr:2019
+hcl:#602927 eyr:1967 hgt:170cm
+ecl:grn pid:012533040 byr:1946
+
+hcl:dab227 iyr:2012
+ecl:brn hgt:182cm pid:021572410 eyr:2020 byr:1992 cid:277
+
+hgt:59cm ecl:zzz
+eyr:2038 hcl:74454a iyr:2023
+pid:3556412378 byr:2007
+";
+
+    let valid = "pid:087499704 hgt:74in ecl:grn iyr:2012 eyr:2030 byr:1980
+hcl:#623a2f
+
+eyr:2029 ecl:blu cid:129 byr:1989
+iyr:2014 pid:896056539 hcl:#a97842 hgt:165cm
+
+hcl:#888785
+hgt:164cm byr:2001 iyr:2015 cid:88
+pid:545766238 ecl:hzl
+eyr:2022
+
+iyr:2010 hgt:158cm hcl:#b6652a ecl:blu byr:1944 eyr:2021 pid:093154719
+";
 
-    println!("Valid passport count: {}", valid_passport_count);
+    assert_eq!(crate::runner::run_example(4, 1, example, &[]), "2");
+    assert_eq!(crate::runner::run_example(4, 2, invalid, &[]), "0");
+    assert_eq!(crate::runner::run_example(4, 2, valid, &[]), "4");
 }
@@ -1,8 +1,14 @@
-use crate::utils;
-use std::{str::FromStr, string::ParseError};
+use crate::{
+    answer::Answer,
+    error::{ParseError, SolveError},
+    generate::{self, Rng},
+    input::Input,
+    solver::{Registry, Solver},
+};
+use std::str::FromStr;
 
 #[derive(Debug)]
-struct BoardingPass {
+pub struct BoardingPass {
     seat_code: String,
     num_rows: u8,
     num_cols: u8,
@@ -11,7 +17,7 @@ struct BoardingPass {
 }
 
 impl BoardingPass {
-    pub fn new(seat_code: String) -> Self {
+    pub fn new(seat_code: String) -> Result<Self, ParseError> {
         let mut instance = Self {
             seat_code: String::new(),
             num_rows: 128,
@@ -20,9 +26,9 @@ impl BoardingPass {
             col: 0,
         };
 
-        instance.parse(seat_code);
+        instance.parse(seat_code)?;
 
-        instance
+        Ok(instance)
     }
 
     fn partition(&self, min: u8, max: u8, lower_half: bool) -> (u8, u8) {
@@ -35,27 +41,66 @@ impl BoardingPass {
         }
     }
 
-    fn parse(&mut self, seat_code: String) {
+    fn parse(&mut self, seat_code: String) -> Result<(), ParseError> {
         self.seat_code = seat_code;
 
+        let row_code_len = self.num_rows.trailing_zeros() as usize;
+        let col_code_len = self.num_cols.trailing_zeros() as usize;
+
         let mut row_bounds = (0, self.num_rows - 1);
         let mut col_bounds = (0, self.num_cols - 1);
-
-        for c in self.seat_code.chars() {
-            match c {
-                'F' => row_bounds = self.partition(row_bounds.0, row_bounds.1, true),
-                'B' => row_bounds = self.partition(row_bounds.0, row_bounds.1, false),
-                'L' => col_bounds = self.partition(col_bounds.0, col_bounds.1, true),
-                'R' => col_bounds = self.partition(col_bounds.0, col_bounds.1, false),
-                _ => panic!("Unexpected seat code character {}", c),
+        let mut code_len = 0;
+
+        for (byte_pos, c) in self.seat_code.char_indices() {
+            let is_row_code = code_len < row_code_len;
+            let is_col_code = !is_row_code && code_len < row_code_len + col_code_len;
+
+            match (c, is_row_code, is_col_code) {
+                ('F', true, _) => row_bounds = self.partition(row_bounds.0, row_bounds.1, true),
+                ('B', true, _) => row_bounds = self.partition(row_bounds.0, row_bounds.1, false),
+                ('L', _, true) => col_bounds = self.partition(col_bounds.0, col_bounds.1, true),
+                ('R', _, true) => col_bounds = self.partition(col_bounds.0, col_bounds.1, false),
+                _ => {
+                    let fragment = &self.seat_code[byte_pos..byte_pos + c.len_utf8()];
+                    let expected = if is_row_code {
+                        "'F' or 'B'"
+                    } else if is_col_code {
+                        "'L' or 'R'"
+                    } else {
+                        "end of seat code"
+                    };
+
+                    return Err(ParseError::at(&self.seat_code, fragment, expected));
+                }
             }
+
+            code_len += 1;
         }
 
-        assert_eq!(row_bounds.0, row_bounds.1);
-        self.row = row_bounds.0;
+        if code_len != row_code_len + col_code_len {
+            return Err(ParseError::new(
+                code_len + 1,
+                "",
+                &format!("a seat code of {} characters", row_code_len + col_code_len),
+            ));
+        }
 
-        assert_eq!(col_bounds.0, col_bounds.1);
+        self.row = row_bounds.0;
         self.col = col_bounds.0;
+
+        Ok(())
+    }
+
+    pub fn seat_code(&self) -> &str {
+        &self.seat_code
+    }
+
+    pub fn row(&self) -> u8 {
+        self.row
+    }
+
+    pub fn col(&self) -> u8 {
+        self.col
     }
 
     pub fn get_seat_id(&self) -> usize {
@@ -65,63 +110,127 @@ impl BoardingPass {
 
 #[test]
 fn test_parse() {
-    let boarding_pass = BoardingPass::new("BFFFBBFRRR".to_string());
+    let boarding_pass = BoardingPass::new("BFFFBBFRRR".to_string()).unwrap();
     assert_eq!(boarding_pass.row, 70);
     assert_eq!(boarding_pass.col, 7);
     assert_eq!(boarding_pass.get_seat_id(), 567);
 
-    let boarding_pass = BoardingPass::new("FFFBBBFRRR".to_string());
+    let boarding_pass = BoardingPass::new("FFFBBBFRRR".to_string()).unwrap();
     assert_eq!(boarding_pass.row, 14);
     assert_eq!(boarding_pass.col, 7);
     assert_eq!(boarding_pass.get_seat_id(), 119);
 
-    let boarding_pass = BoardingPass::new("BBFFBBFRLL".to_string());
+    let boarding_pass = BoardingPass::new("BBFFBBFRLL".to_string()).unwrap();
     assert_eq!(boarding_pass.row, 102);
     assert_eq!(boarding_pass.col, 4);
     assert_eq!(boarding_pass.get_seat_id(), 820);
 }
 
+#[test]
+fn test_parse_errors() {
+    let error = BoardingPass::new("BFFFBBFRXR".to_string()).unwrap_err();
+    assert_eq!(error.column, 9);
+    assert_eq!(error.text, "X");
+    assert_eq!(error.expected, "'L' or 'R'");
+
+    let error = BoardingPass::new("BFFRBBFRRR".to_string()).unwrap_err();
+    assert_eq!(error.column, 4);
+    assert_eq!(error.expected, "'F' or 'B'");
+
+    let error = BoardingPass::new("BFFFBBFRR".to_string()).unwrap_err();
+    assert_eq!(error.column, 10);
+
+    let error = BoardingPass::new("BFFFBBFRRRR".to_string()).unwrap_err();
+    assert_eq!(error.column, 11);
+    assert_eq!(error.expected, "end of seat code");
+}
+
 impl FromStr for BoardingPass {
     type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(BoardingPass::new(s.to_string()))
+        BoardingPass::new(s.to_string())
     }
 }
 
-pub fn part1() {
-    let boarding_passes = utils::stdin_to_vec::<BoardingPass>();
-
-    let mut max_seat_id = 0;
-    for boarding_pass in boarding_passes {
-        let seat_id = boarding_pass.get_seat_id();
+pub struct Day5 {
+    boarding_passes: Vec<BoardingPass>,
+}
 
-        if seat_id > max_seat_id {
-            max_seat_id = seat_id;
-        }
+impl Solver for Day5 {
+    fn parse(input: &Input) -> Result<Self, ParseError> {
+        Ok(Self {
+            boarding_passes: input.lines::<BoardingPass>()?,
+        })
     }
 
-    println!("Max boarding pass seat id: {}", max_seat_id);
-}
+    fn generate(rng: &mut Rng, size: usize) -> Option<String> {
+        let seat_ids = generate::distinct(rng, (0..1024).collect(), size);
+
+        Some(
+            seat_ids
+                .iter()
+                .map(|seat_id: &usize| {
+                    (0..10)
+                        .rev()
+                        .map(|bit| match (bit >= 3, seat_id >> bit & 1 == 1) {
+                            (true, false) => 'F',
+                            (true, true) => 'B',
+                            (false, false) => 'L',
+                            (false, true) => 'R',
+                        })
+                        .collect::<String>()
+                        + "\n"
+                })
+                .collect(),
+        )
+    }
 
-pub fn part2() {
-    let boarding_passes = utils::stdin_to_vec::<BoardingPass>();
+    fn part1(&self) -> Result<Option<Answer>, SolveError> {
+        let mut max_seat_id = 0;
+        for boarding_pass in &self.boarding_passes {
+            let seat_id = boarding_pass.get_seat_id();
 
-    let mut seat_ids: Vec<usize> = vec![];
-    for boarding_pass in boarding_passes {
-        if boarding_pass.row == 0 || boarding_pass.row == (boarding_pass.num_rows - 1) {
-            println!("Skipping {:?}", boarding_pass);
-            continue;
+            if seat_id > max_seat_id {
+                max_seat_id = seat_id;
+            }
         }
 
-        seat_ids.push(boarding_pass.get_seat_id());
+        Ok(Some(Answer::from(max_seat_id)))
     }
 
-    seat_ids.sort();
+    fn part2(&self) -> Result<Option<Answer>, SolveError> {
+        let mut seat_ids: Vec<usize> = vec![];
+        for boarding_pass in &self.boarding_passes {
+            if boarding_pass.row == 0 || boarding_pass.row == (boarding_pass.num_rows - 1) {
+                crate::debug!("Skipping {:?}", boarding_pass);
+                continue;
+            }
 
-    for i in 0..seat_ids.len() - 1 {
-        if seat_ids[i + 1] == (seat_ids[i] + 2) {
-            println!("Found seat: {}", seat_ids[i] + 1);
+            seat_ids.push(boarding_pass.get_seat_id());
         }
+
+        seat_ids.sort();
+
+        for pair in seat_ids.windows(2) {
+            if pair[1] == (pair[0] + 2) {
+                return Ok(Some(Answer::from(pair[0] + 1)));
+            }
+        }
+
+        Ok(None)
     }
 }
+
+pub fn register(registry: &mut Registry) {
+    registry.register::<Day5>(5);
+}
+
+#[test]
+fn test_examples() {
+    let example = "FBFBBFFRLR\nBFFFBBFRRR\nFFFBBBFRRR\nBBFFBBFRLL\n";
+    let missing_seat = "FFFFFFBLLL\nFFFFFFBLLR\nFFFFFFBLRR\nFFFFFFBRLL\n";
+
+    assert_eq!(crate::runner::run_example(5, 1, example, &[]), "820");
+    assert_eq!(crate::runner::run_example(5, 2, missing_seat, &[]), "10");
+}
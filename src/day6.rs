@@ -1,14 +1,22 @@
-use io::stdin;
-use std::io::{self, Read};
+use crate::{
+    answer::Answer,
+    error::{ParseError, SolveError},
+    generate::{self, Rng},
+    input::Input,
+    solver::{Registry, Solver},
+    utils,
+};
+use rand::Rng as _;
+use std::str::FromStr;
 
 #[derive(Debug)]
-struct Passenger {
-    questions: String,
+pub struct Passenger {
+    pub questions: String,
 }
 
-#[derive(Debug)]
-struct PassengerGroup {
-    passengers: Vec<Passenger>,
+#[derive(Debug, Default)]
+pub struct PassengerGroup {
+    pub passengers: Vec<Passenger>,
 }
 
 impl Passenger {
@@ -17,16 +25,32 @@ impl Passenger {
     }
 }
 
+impl FromStr for Passenger {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some((pos, c)) = s.char_indices().find(|(_, c)| !c.is_ascii_lowercase()) {
+            return Err(ParseError::at(
+                s,
+                &s[pos..pos + c.len_utf8()],
+                "a question letter 'a'-'z'",
+            ));
+        }
+
+        Ok(Passenger::new(s.to_string()))
+    }
+}
+
 impl PassengerGroup {
     pub fn new() -> Self {
         Self { passengers: vec![] }
     }
 
-    fn add_passenger(&mut self, passenger: Passenger) {
+    pub fn add_passenger(&mut self, passenger: Passenger) {
         self.passengers.push(passenger);
     }
 
-    fn get_unique_questions(&self) -> String {
+    pub fn get_unique_questions(&self) -> String {
         let mut unique_questions = String::new();
 
         for passenger in &self.passengers {
@@ -40,7 +64,7 @@ impl PassengerGroup {
         unique_questions
     }
 
-    fn get_common_questions(&self) -> String {
+    pub fn get_common_questions(&self) -> String {
         let mut common_questions = String::new();
 
         if self.passengers.is_empty() {
@@ -75,44 +99,84 @@ impl PassengerGroup {
     }
 }
 
-fn stdin_to_passenger_groups() -> Vec<PassengerGroup> {
-    let mut input_str = String::new();
-    let _ = stdin().read_to_string(&mut input_str);
+pub fn input_to_passenger_groups(input: &Input) -> Result<Vec<PassengerGroup>, ParseError> {
+    let mut passenger_groups: Vec<PassengerGroup> = vec![];
 
-    let mut passenger_groups: Vec<PassengerGroup> = vec![PassengerGroup::new()];
+    for group in utils::record_groups(input.text()) {
+        let mut passenger_group = PassengerGroup::new();
 
-    for field_str in input_str.split('\n') {
-        if field_str.trim().is_empty() {
-            passenger_groups.push(PassengerGroup::new());
-            continue;
+        for record_line in group {
+            match record_line.text.parse::<Passenger>() {
+                Ok(passenger) => passenger_group.add_passenger(passenger),
+                Err(err) => input.reject(err.with_line(record_line.line_num, record_line.text))?,
+            }
         }
 
-        let passenger_group = passenger_groups.last_mut().unwrap();
-        passenger_group.add_passenger(Passenger::new(field_str.to_string()));
+        passenger_groups.push(passenger_group);
     }
 
-    println!("{:?}", passenger_groups);
-    passenger_groups
+    crate::trace!("{:?}", passenger_groups);
+    input.finish(passenger_groups)
+}
+
+pub struct Day6 {
+    passenger_groups: Vec<PassengerGroup>,
 }
 
-pub fn part1() {
-    let passenger_groups = stdin_to_passenger_groups();
+impl Solver for Day6 {
+    fn parse(input: &Input) -> Result<Self, ParseError> {
+        Ok(Self {
+            passenger_groups: input_to_passenger_groups(input)?,
+        })
+    }
 
-    let total_questions = passenger_groups
-        .iter()
-        .map(|pg| pg.get_unique_questions().chars().count())
-        .sum::<usize>();
+    fn generate(rng: &mut Rng, size: usize) -> Option<String> {
+        let questions = ('a'..='z').collect::<Vec<char>>();
+
+        let groups = (0..size)
+            .map(|_| {
+                let person_count = rng.gen_range(1..=5);
+                generate::lines(rng, person_count, |rng| {
+                    let question_count = rng.gen_range(1..=questions.len());
+                    generate::distinct(rng, questions.clone(), question_count)
+                        .iter()
+                        .collect()
+                })
+            })
+            .collect::<Vec<String>>();
+
+        Some(groups.join("\n"))
+    }
 
-    println!("Total number of unique questions: {}", total_questions);
+    fn part1(&self) -> Result<Option<Answer>, SolveError> {
+        let total_questions = self
+            .passenger_groups
+            .iter()
+            .map(|pg| pg.get_unique_questions().chars().count())
+            .sum::<usize>();
+
+        Ok(Some(Answer::from(total_questions)))
+    }
+
+    fn part2(&self) -> Result<Option<Answer>, SolveError> {
+        let total_questions = self
+            .passenger_groups
+            .iter()
+            .map(|pg| pg.get_common_questions().chars().count())
+            .sum::<usize>();
+
+        Ok(Some(Answer::from(total_questions)))
+    }
 }
 
-pub fn part2() {
-    let passenger_groups = stdin_to_passenger_groups();
+pub fn register(registry: &mut Registry) {
+    registry.register::<Day6>(6);
+}
 
-    let total_questions = passenger_groups
-        .iter()
-        .map(|pg| pg.get_common_questions().chars().count())
-        .sum::<usize>();
+#[test]
+fn test_examples() {
+    let example = "abc\n\na\nb\nc\n\nab\nac\n\na\na\na\na\n\nb\n";
 
-    println!("Total number of common questions: {}", total_questions);
+    assert_eq!(crate::runner::run_example(6, 1, example, &[]), "11");
+    assert_eq!(crate::runner::run_example(6, 2, example, &[]), "6");
 }
@@ -1,11 +1,18 @@
-use crate::utils;
-use std::{fmt::Debug, str::FromStr, string::ParseError};
-use utils::stdin_to_vec;
+use crate::{
+    answer::Answer,
+    cancel,
+    error::{ParseError, SolveError},
+    generate::Rng,
+    input::Input,
+    solver::{Param, Registry, Solver},
+};
+use rand::{seq::SliceRandom, Rng as _};
+use std::{collections::BTreeSet, fmt::Debug, str::FromStr};
 
 #[derive(Debug, Clone, PartialEq, Default)]
-struct Bag {
-    bag_type: String,
-    contents: Vec<Bag>,
+pub struct Bag {
+    pub bag_type: String,
+    pub contents: Vec<Bag>,
 }
 
 impl Bag {
@@ -13,45 +20,42 @@ impl Bag {
         Self { bag_type, contents }
     }
 
-    pub fn parse(&mut self, rule: &str) {
-        let rule = rule
-            .replace("bags", "")
-            .replace("bag", "")
-            .replace("contain", ",")
-            .replace("no other", "0")
-            .replace(".", "");
-
-        let rule_items: Vec<&str> = rule.split(',').collect();
-        self.bag_type = rule_items[0].trim().to_string();
-
-        for i in 1..rule_items.len() {
-            let rule_item = rule_items[i].trim();
-            if rule_item.trim().is_empty() {
-                continue;
-            }
+    pub fn parse(&mut self, rule: &str) -> Result<(), ParseError> {
+        let (bag_type, contents) = rule
+            .split_once(" bags contain ")
+            .ok_or_else(|| ParseError::at(rule, rule, "'<bag type> bags contain <contents>.'"))?;
 
-            let mut contained_bag_split = rule_item.splitn(2, ' ');
-            let contained_bag_count = contained_bag_split
-                .next()
-                .unwrap()
-                .parse::<usize>()
-                .unwrap();
+        let contents = contents
+            .strip_suffix('.')
+            .ok_or_else(|| ParseError::at(rule, &rule[rule.len()..], "'.'"))?;
 
-            if contained_bag_count == 0 {
-                continue;
-            }
+        self.bag_type = bag_type.trim().to_string();
+
+        if contents == "no other bags" {
+            return Ok(());
+        }
+
+        for rule_item in contents.split(", ") {
+            let (contained_bag_count, contained_bag_type) = rule_item
+                .split_once(' ')
+                .ok_or_else(|| ParseError::at(rule, rule_item, "'<count> <bag type> bag(s)'"))?;
 
-            let contained_bag_type = contained_bag_split
-                .next()
-                .unwrap()
-                .parse::<String>()
-                .unwrap();
+            let contained_bag_count = contained_bag_count
+                .parse::<usize>()
+                .map_err(|_| ParseError::at(rule, contained_bag_count, "a bag count"))?;
+
+            let contained_bag_type = contained_bag_type
+                .strip_suffix(" bags")
+                .or_else(|| contained_bag_type.strip_suffix(" bag"))
+                .ok_or_else(|| ParseError::at(rule, contained_bag_type, "'<bag type> bag(s)'"))?;
 
             for _ in 0..contained_bag_count {
                 self.contents
                     .push(Bag::new(contained_bag_type.to_string(), vec![]));
             }
         }
+
+        Ok(())
     }
 }
 
@@ -64,13 +68,17 @@ impl FromStr for Bag {
             contents: vec![],
         };
 
-        instance.parse(s);
+        instance.parse(s)?;
 
         Ok(instance)
     }
 }
 
-fn find_parents(bags: &Vec<Bag>, contained_bag_type: &str, parent_bags: &mut Vec<Bag>) {
+pub fn find_parents(bags: &[Bag], contained_bag_type: &str, parent_bags: &mut Vec<Bag>) {
+    if cancel::is_cancelled() {
+        return;
+    }
+
     bags.iter()
         .filter(|bag| {
             bag.contents
@@ -87,7 +95,11 @@ fn find_parents(bags: &Vec<Bag>, contained_bag_type: &str, parent_bags: &mut Vec
         });
 }
 
-fn find_contained_bag_count(bags: &Vec<Bag>, bag_type: &str, contained_bag_count: &mut usize) {
+pub fn find_contained_bag_count(bags: &[Bag], bag_type: &str, contained_bag_count: &mut usize) {
+    if cancel::is_cancelled() {
+        return;
+    }
+
     bags.iter()
         .filter(|bag| bag.bag_type.eq(bag_type))
         .for_each(|bag| {
@@ -103,22 +115,185 @@ fn find_contained_bag_count(bags: &Vec<Bag>, bag_type: &str, contained_bag_count
         });
 }
 
-pub fn part1() {
-    let bags = stdin_to_vec::<Bag>();
-    let contained_bag_type = "shiny gold";
-    let mut parent_bags = vec![];
+pub const BAG_TYPE: &str = "shiny gold";
+
+pub struct Day7 {
+    bags: Vec<Bag>,
+    bag_type: String,
+}
+
+impl Solver for Day7 {
+    fn params() -> Vec<Param> {
+        vec![Param::new("bag", BAG_TYPE)]
+    }
+
+    fn parse(input: &Input) -> Result<Self, ParseError> {
+        Ok(Self {
+            bags: input.lines::<Bag>()?,
+            bag_type: input.param("bag", BAG_TYPE.to_string())?,
+        })
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> Option<String> {
+        let adjectives = [
+            "bright", "clear", "dark", "dim", "dotted", "drab", "dull", "faded", "light",
+            "mirrored", "muted", "pale", "plaid", "posh", "shiny", "vibrant",
+        ];
+        let colors = [
+            "aqua", "beige", "black", "blue", "bronze", "coral", "gold", "green", "lime", "olive",
+            "orange", "plum", "red", "tan", "white", "yellow",
+        ];
+
+        let bag_type = |i: usize| {
+            let round = i / (adjectives.len() * colors.len());
+            let adjective = adjectives[i % adjectives.len()];
+            let color = colors[i / adjectives.len() % colors.len()];
+
+            match round {
+                0 => format!("{} {}", adjective, color),
+                _ => format!("{}{} {}", adjective, round, color),
+            }
+        };
+
+        let mut bag_types = (0..size.max(1)).map(bag_type).collect::<Vec<String>>();
+        bag_types.retain(|bag_type| bag_type != BAG_TYPE);
+        bag_types.shuffle(rng);
+        bag_types.insert(size / 2, BAG_TYPE.to_string());
+        bag_types.truncate(size.max(1));
+
+        let rules = (0..bag_types.len())
+            .map(|i| {
+                let remaining = bag_types.len() - i - 1;
+                let content_count = rng.gen_range(0..=4.min(remaining));
+
+                let contents = (0..content_count)
+                    .map(|_| rng.gen_range(i + 1..bag_types.len()))
+                    .collect::<BTreeSet<usize>>()
+                    .iter()
+                    .map(|j| match rng.gen_range(1..=5) {
+                        1 => format!("1 {} bag", bag_types[*j]),
+                        count => format!("{} {} bags", count, bag_types[*j]),
+                    })
+                    .collect::<Vec<String>>();
+
+                match contents.is_empty() {
+                    true => format!("{} bags contain no other bags.\n", bag_types[i]),
+                    false => format!("{} bags contain {}.\n", bag_types[i], contents.join(", ")),
+                }
+            })
+            .collect();
+
+        Some(rules)
+    }
+
+    fn part1(&self) -> Result<Option<Answer>, SolveError> {
+        let mut parent_bags = vec![];
+
+        find_parents(&self.bags, &self.bag_type, &mut parent_bags);
+
+        Ok(Some(Answer::from(parent_bags.len())))
+    }
+
+    fn part2(&self) -> Result<Option<Answer>, SolveError> {
+        let mut contained_bag_count: usize = 0;
+
+        find_contained_bag_count(&self.bags, &self.bag_type, &mut contained_bag_count);
+
+        Ok(Some(Answer::from(contained_bag_count)))
+    }
+
+    fn values(&self) -> Vec<&'static str> {
+        vec!["bags", "bag <bag type>", "parents [bag type]"]
+    }
+
+    fn value(&self, name: &str, args: &[&str]) -> Result<String, String> {
+        let bag_type = if args.is_empty() {
+            self.bag_type.clone()
+        } else {
+            args.join(" ")
+        };
+
+        match name {
+            "bags" => Ok(self
+                .bags
+                .iter()
+                .map(|bag| format!("{:?}", bag))
+                .collect::<Vec<String>>()
+                .join("\n")),
+            "bag" => self
+                .bags
+                .iter()
+                .find(|bag| bag.bag_type == bag_type)
+                .map(|bag| format!("{:?}", bag))
+                .ok_or_else(|| format!("no bag '{}'", bag_type)),
+            "parents" => {
+                let mut parent_bags = vec![];
+                find_parents(&self.bags, &bag_type, &mut parent_bags);
+                Ok(parent_bags
+                    .iter()
+                    .map(|bag| bag.bag_type.as_str())
+                    .collect::<Vec<&str>>()
+                    .join("\n"))
+            }
+            _ => Err(format!("unknown value '{}'", name)),
+        }
+    }
+}
+
+pub fn register(registry: &mut Registry) {
+    registry.register::<Day7>(7);
+}
+
+#[test]
+fn test_examples() {
+    let example = "light red bags contain 1 bright white bag, 2 muted yellow bags.
+dark orange bags contain 3 bright white bags, 4 muted yellow bags.
+bright white bags contain 1 shiny gold bag.
+muted yellow bags contain 2 shiny gold bags, 9 faded blue bags.
+shiny gold bags contain 1 dark olive bag, 2 vibrant plum bags.
+dark olive bags contain 3 faded blue bags, 4 dotted black bags.
+vibrant plum bags contain 5 faded blue bags, 6 dotted black bags.
+faded blue bags contain no other bags.
+dotted black bags contain no other bags.
+";
 
-    find_parents(&bags, contained_bag_type, &mut parent_bags);
+    let nested = "shiny gold bags contain 2 dark red bags.
+dark red bags contain 2 dark orange bags.
+dark orange bags contain 2 dark yellow bags.
+dark yellow bags contain 2 dark green bags.
+dark green bags contain 2 dark blue bags.
+dark blue bags contain 2 dark violet bags.
+dark violet bags contain no other bags.
+";
 
-    println!("Parent bags: {}", parent_bags.len());
+    assert_eq!(crate::runner::run_example(7, 1, example, &[]), "4");
+    assert_eq!(crate::runner::run_example(7, 2, example, &[]), "32");
+    assert_eq!(crate::runner::run_example(7, 2, nested, &[]), "126");
 }
 
-pub fn part2() {
-    let bags = stdin_to_vec::<Bag>();
-    let bag_type = "shiny gold";
-    let mut contained_bag_count: usize = 0;
+#[test]
+fn test_generated_part2_cancels() {
+    use crate::{
+        cancel::CancelToken,
+        runner::{self, RunError},
+    };
+    use std::time::{Duration, Instant};
+
+    let text = Day7::generate(
+        &mut crate::generate::rng(crate::generate::DEFAULT_SEED),
+        400,
+    )
+    .unwrap();
+    let token = CancelToken::with_timeout(Duration::from_millis(100));
+    token.start_timer();
 
-    find_contained_bag_count(&bags, bag_type, &mut contained_bag_count);
+    let start = Instant::now();
+    let runs =
+        runner::run_parts_cancellable(&crate::registry(), 7, &[2], &Input::new(&text), &token);
 
-    println!("Contained bags: {}", contained_bag_count);
+    assert!(start.elapsed() < Duration::from_secs(5));
+    assert_eq!(
+        runs[0].answer,
+        Err(RunError::TimedOut(Duration::from_millis(100)))
+    );
 }
@@ -1,37 +1,63 @@
-use crate::utils;
-use std::{fmt::Debug, str::FromStr, string::ParseError};
-use utils::stdin_to_vec;
+use crate::{
+    answer::Answer,
+    cancel,
+    error::{ParseError, SolveError},
+    generate::Rng,
+    input::Input,
+    solver::{self, Registry, Solver},
+};
+use rand::Rng as _;
+use std::{fmt::Debug, str::FromStr};
 
 #[derive(Debug, PartialEq, Clone)]
-enum Operation {
-    ACC,
-    JMP,
-    NOP,
+pub enum Operation {
+    Acc,
+    Jmp,
+    Nop,
 }
 
-impl Operation {
-    pub fn from(op_string: &str) -> Operation {
+impl FromStr for Operation {
+    type Err = ParseError;
+
+    fn from_str(op_string: &str) -> Result<Self, Self::Err> {
         match op_string {
-            "acc" => Operation::ACC,
-            "jmp" => Operation::JMP,
-            "nop" => Operation::NOP,
-            _ => panic!("Unexpected operation: {}", op_string),
+            "acc" => Ok(Operation::Acc),
+            "jmp" => Ok(Operation::Jmp),
+            "nop" => Ok(Operation::Nop),
+            _ => Err(ParseError::at(
+                op_string,
+                op_string,
+                "'acc', 'jmp' or 'nop'",
+            )),
         }
     }
 }
+
 #[derive(Debug, Clone)]
-struct Instruction {
-    operation: Operation,
-    argument: i32,
+pub struct Instruction {
+    pub operation: Operation,
+    pub argument: i32,
 }
 
 impl Instruction {
-    pub fn parse(&mut self, instruction_line: &str) {
-        let instruction_items: Vec<&str> = instruction_line.split(' ').collect();
-        assert_eq!(instruction_items.len(), 2);
+    pub fn parse(&mut self, instruction_line: &str) -> Result<(), ParseError> {
+        let (operation, argument) = instruction_line.split_once(' ').ok_or_else(|| {
+            ParseError::at(
+                instruction_line,
+                instruction_line,
+                "'<operation> <argument>'",
+            )
+        })?;
 
-        self.operation = Operation::from(instruction_items[0]);
-        self.argument = instruction_items[1].parse::<i32>().unwrap();
+        self.operation = operation
+            .parse::<Operation>()
+            .map_err(|err| ParseError::at(instruction_line, operation, &err.expected))?;
+
+        self.argument = argument
+            .parse::<i32>()
+            .map_err(|_| ParseError::at(instruction_line, argument, "a signed integer argument"))?;
+
+        Ok(())
     }
 }
 
@@ -40,18 +66,18 @@ impl FromStr for Instruction {
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let mut instance = Self {
-            operation: Operation::NOP,
+            operation: Operation::Nop,
             argument: 0,
         };
 
-        instance.parse(s);
+        instance.parse(s)?;
 
         Ok(instance)
     }
 }
 
 #[derive(Debug)]
-struct Program {
+pub struct Program {
     instructions: Vec<Instruction>,
     accumulator: i32,
     instruction_index: i32,
@@ -59,20 +85,19 @@ struct Program {
 }
 
 impl Program {
-    pub fn new(instructions: &Vec<Instruction>) -> Self {
+    pub fn new(instructions: &[Instruction]) -> Self {
         Self {
-            instructions: instructions.clone(),
+            instructions: instructions.to_vec(),
             accumulator: 0,
             instruction_index: 0,
             executed_instruction_indexes: vec![],
         }
     }
 
-    fn exec_instruction(&mut self) {
-        assert!(
-            self.instruction_index >= 0
-                && (self.instruction_index as usize) < self.instructions.len()
-        );
+    pub fn exec_instruction(&mut self) {
+        if self.is_terminated_normally() || self.is_terminated_abnormally() {
+            return;
+        }
 
         let instruction = &self.instructions[self.instruction_index as usize];
 
@@ -80,82 +105,193 @@ impl Program {
             .push(self.instruction_index);
 
         match instruction.operation {
-            Operation::NOP => self.instruction_index += 1,
-            Operation::JMP => self.instruction_index += instruction.argument,
-            Operation::ACC => {
+            Operation::Nop => self.instruction_index += 1,
+            Operation::Jmp => self.instruction_index += instruction.argument,
+            Operation::Acc => {
                 self.accumulator += instruction.argument;
                 self.instruction_index += 1
             }
         }
 
-        println!(
+        crate::trace!(
             "Executed instruction: {:?}, accumulator: {}",
-            instruction, self.accumulator
+            instruction,
+            self.accumulator
         );
     }
 
+    pub fn accumulator(&self) -> i32 {
+        self.accumulator
+    }
+
+    pub fn instruction_index(&self) -> i32 {
+        self.instruction_index
+    }
+
     pub fn is_loop_detected(&self) -> bool {
         self.executed_instruction_indexes
             .contains(&self.instruction_index)
     }
 
     pub fn is_terminated_normally(&self) -> bool {
-        self.instruction_index >= (self.instructions.len() as i32)
+        self.instruction_index == (self.instructions.len() as i32)
     }
 
-    fn reset(&mut self) {
+    pub fn is_terminated_abnormally(&self) -> bool {
+        self.instruction_index < 0 || self.instruction_index > (self.instructions.len() as i32)
+    }
+
+    pub fn reset(&mut self) {
         self.accumulator = 0;
         self.instruction_index = 0;
         self.executed_instruction_indexes = vec![];
     }
 
     pub fn run(&mut self) {
-        &self.reset();
+        self.reset();
+
+        while !self.is_loop_detected()
+            && !self.is_terminated_normally()
+            && !self.is_terminated_abnormally()
+        {
+            if cancel::is_cancelled() {
+                return;
+            }
 
-        while !self.is_loop_detected() && !self.is_terminated_normally() {
             self.exec_instruction();
         }
     }
 }
 
-pub fn part1() {
-    let instructions = stdin_to_vec::<Instruction>();
+pub struct Day8 {
+    instructions: Vec<Instruction>,
+}
 
-    let mut program = Program::new(&instructions);
-    program.run();
+impl Solver for Day8 {
+    fn parse(input: &Input) -> Result<Self, ParseError> {
+        Ok(Self {
+            instructions: input.lines::<Instruction>()?,
+        })
+    }
 
-    println!("Accumulator: {}", program.accumulator);
-}
+    fn generate(rng: &mut Rng, size: usize) -> Option<String> {
+        let instructions = (0..size)
+            .map(|i| match rng.gen_range(0..3) {
+                0 => format!("acc {:+}\n", rng.gen_range(-50..=50)),
+                1 => format!("jmp {:+}\n", rng.gen_range(0..=size) as i64 - i as i64),
+                _ => format!("nop {:+}\n", rng.gen_range(-50..=50)),
+            })
+            .collect();
 
-pub fn part2() {
-    let instructions = &stdin_to_vec::<Instruction>();
+        Some(instructions)
+    }
 
-    for i in 0..instructions.len() {
-        let operation = &instructions[i].operation;
+    fn part1(&self) -> Result<Option<Answer>, SolveError> {
+        let mut program = Program::new(&self.instructions);
+        program.run();
 
-        let try_operation = match operation {
-            Operation::NOP => Some(Operation::JMP),
-            Operation::JMP => Some(Operation::NOP),
-            _ => None,
-        };
+        Ok(Some(Answer::from(program.accumulator)))
+    }
+
+    fn part2(&self) -> Result<Option<Answer>, SolveError> {
+        let instructions = &self.instructions;
 
-        if let Some(try_operation) = try_operation {
-            let mut try_instructions = instructions.clone();
-            try_instructions[i].operation = try_operation;
-
-            let mut program = Program::new(&try_instructions);
-            program.run();
-
-            if program.is_terminated_normally() {
-                println!(
-                    "Program terminated normally after fixing instruction {} from {:?} to {:?}.\nAccumulator: {}",
-                    i,
-                    &instructions[i],
-                    &try_instructions[i],
-                    program.accumulator
-                );
-                break;
+        for i in 0..instructions.len() {
+            let operation = &instructions[i].operation;
+
+            let try_operation = match operation {
+                Operation::Nop => Some(Operation::Jmp),
+                Operation::Jmp => Some(Operation::Nop),
+                _ => None,
+            };
+
+            if let Some(try_operation) = try_operation {
+                let mut try_instructions = instructions.clone();
+                try_instructions[i].operation = try_operation;
+
+                let mut program = Program::new(&try_instructions);
+                program.run();
+
+                if cancel::is_cancelled() {
+                    return Ok(None);
+                }
+
+                if program.is_terminated_normally() {
+                    crate::debug!(
+                        "Program terminated normally after fixing instruction {} from {:?} to {:?}.",
+                        i, &instructions[i], &try_instructions[i]
+                    );
+                    return Ok(Some(Answer::from(program.accumulator)));
+                }
             }
         }
+
+        Ok(None)
     }
+
+    fn values(&self) -> Vec<&'static str> {
+        vec!["instructions", "program [steps]"]
+    }
+
+    fn value(&self, name: &str, args: &[&str]) -> Result<String, String> {
+        match name {
+            "instructions" => Ok(self
+                .instructions
+                .iter()
+                .enumerate()
+                .map(|(i, instruction)| format!("{:>4}: {:?}", i, instruction))
+                .collect::<Vec<String>>()
+                .join("\n")),
+            "program" => {
+                let steps = solver::value_arg(args, 0, usize::MAX)?;
+                let mut program = Program::new(&self.instructions);
+
+                let mut executed = 0;
+                while executed < steps
+                    && !program.is_loop_detected()
+                    && !program.is_terminated_normally()
+                    && !program.is_terminated_abnormally()
+                {
+                    program.exec_instruction();
+                    executed += 1;
+                }
+
+                Ok(format!(
+                    "steps: {}, accumulator: {}, instruction index: {}, loop detected: {}, terminated: {}",
+                    executed,
+                    program.accumulator(),
+                    program.instruction_index(),
+                    program.is_loop_detected(),
+                    program.is_terminated_normally()
+                ))
+            }
+            _ => Err(format!("unknown value '{}'", name)),
+        }
+    }
+}
+
+pub fn register(registry: &mut Registry) {
+    registry.register::<Day8>(8);
+}
+
+#[test]
+fn test_examples() {
+    let example = "nop +0\nacc +1\njmp +4\nacc +3\njmp -3\nacc -99\nacc +1\njmp -4\nacc +6\n";
+
+    assert_eq!(crate::runner::run_example(8, 1, example, &[]), "5");
+    assert_eq!(crate::runner::run_example(8, 2, example, &[]), "8");
+}
+
+#[test]
+fn test_out_of_range_jumps() {
+    let example = "acc +2\nnop -5\nacc +1\n";
+
+    assert_eq!(crate::runner::run_example(8, 1, example, &[]), "3");
+    assert_eq!(
+        crate::runner::run_example(8, 2, example, &[]),
+        "no answer found"
+    );
+
+    let generated = Day8::generate(&mut crate::generate::rng(3), 30).unwrap();
+    assert_eq!(crate::runner::run_example(8, 2, &generated, &[]), "145");
 }
@@ -1,6 +1,13 @@
-use crate::utils;
+use crate::{
+    answer::Answer,
+    error::{ParseError, SolveError},
+    generate::{self, Rng},
+    input::Input,
+    solver::{Param, Registry, Solver},
+};
+use rand::{seq::SliceRandom, Rng as _};
 
-fn find_first_invalid_number(numbers: &Vec<i64>, preamble_len: usize) -> Option<i64> {
+pub fn find_first_invalid_number(numbers: &[i64], preamble_len: usize) -> Option<i64> {
     for i in preamble_len..numbers.len() {
         let num_to_validate = numbers[i];
         let mut is_valid_num = false;
@@ -12,7 +19,7 @@ fn find_first_invalid_number(numbers: &Vec<i64>, preamble_len: usize) -> Option<
             let preamble_num1 = numbers[j];
             let preamble_num2 = num_to_validate - preamble_num1;
 
-            if (&numbers[(j + 1)..preamble_end]).contains(&preamble_num2) {
+            if numbers[(j + 1)..preamble_end].contains(&preamble_num2) {
                 is_valid_num = true;
                 break;
             }
@@ -26,43 +33,94 @@ fn find_first_invalid_number(numbers: &Vec<i64>, preamble_len: usize) -> Option<
     None
 }
 
-pub fn part1() {
-    let numbers = utils::stdin_to_vec::<i64>();
-    let preamble_len = 25;
+pub fn find_encryption_weakness(numbers: &[i64], invalid_number: i64) -> Option<i64> {
+    for i in 0..numbers.len() {
+        let mut range_number_sum = numbers[i];
+        let mut range_min_number = numbers[i];
+        let mut range_max_number = numbers[i];
 
-    if let Some(invalid_number) = find_first_invalid_number(&numbers, preamble_len) {
-        println!("Number {} is invalid", invalid_number);
+        for number in &numbers[(i + 1)..] {
+            let number = *number;
+            range_number_sum += number;
+
+            if number < range_min_number {
+                range_min_number = number;
+            } else if number > range_max_number {
+                range_max_number = number;
+            }
+
+            if range_number_sum == invalid_number {
+                return Some(range_min_number + range_max_number);
+            } else if range_number_sum > invalid_number {
+                break;
+            }
+        }
     }
+
+    None
 }
 
-pub fn part2() {
-    let numbers = utils::stdin_to_vec::<i64>();
-    let preamble_len = 25;
-
-    if let Some(invalid_number) = find_first_invalid_number(&numbers, preamble_len) {
-        for i in 0..numbers.len() {
-            let mut range_number_sum = numbers[i];
-            let mut range_min_number = numbers[i];
-            let mut range_max_number = numbers[i];
-
-            for j in (i + 1)..numbers.len() {
-                let number = numbers[j];
-                range_number_sum += number;
-
-                if number < range_min_number {
-                    range_min_number = number;
-                } else if number > range_max_number {
-                    range_max_number = number;
-                }
-
-                if range_number_sum == invalid_number {
-                    let result = range_min_number + range_max_number;
-                    println!("Encryption weakness: {}", result);
-                    return;
-                } else if range_number_sum > invalid_number {
-                    break;
-                }
-            }
+pub const PREAMBLE_LEN: usize = 25;
+
+pub struct Day9 {
+    numbers: Vec<i64>,
+    preamble_len: usize,
+}
+
+impl Solver for Day9 {
+    fn params() -> Vec<Param> {
+        vec![Param::new("preamble", PREAMBLE_LEN)]
+    }
+
+    fn parse(input: &Input) -> Result<Self, ParseError> {
+        Ok(Self {
+            numbers: input.lines::<i64>()?,
+            preamble_len: input.param("preamble", PREAMBLE_LEN)?,
+        })
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> Option<String> {
+        let mut numbers: Vec<i64> = vec![];
+
+        for i in 0..size {
+            let number = if i < PREAMBLE_LEN || generate::weighted(rng, 0.01) {
+                rng.gen_range(1..=100)
+            } else {
+                let window = &numbers[i - PREAMBLE_LEN..i];
+                window.choose(rng).unwrap_or(&0) + window.choose(rng).unwrap_or(&0)
+            };
+
+            numbers.push(number);
         }
+
+        Some(
+            numbers
+                .iter()
+                .map(|number| format!("{}\n", number))
+                .collect(),
+        )
+    }
+
+    fn part1(&self) -> Result<Option<Answer>, SolveError> {
+        Ok(find_first_invalid_number(&self.numbers, self.preamble_len).map(Answer::from))
     }
+
+    fn part2(&self) -> Result<Option<Answer>, SolveError> {
+        Ok(find_first_invalid_number(&self.numbers, self.preamble_len)
+            .and_then(|invalid_number| find_encryption_weakness(&self.numbers, invalid_number))
+            .map(Answer::from))
+    }
+}
+
+pub fn register(registry: &mut Registry) {
+    registry.register::<Day9>(9);
+}
+
+#[test]
+fn test_examples() {
+    let example = "35\n20\n15\n25\n47\n40\n62\n55\n65\n95\n102\n117\n150\n182\n127\n219\n299\n277\n309\n576\n";
+    let params = [("preamble", "5")];
+
+    assert_eq!(crate::runner::run_example(9, 1, example, &params), "127");
+    assert_eq!(crate::runner::run_example(9, 2, example, &params), "62");
 }
@@ -0,0 +1,119 @@
+use num_bigint::ParseBigIntError;
+use std::{convert::Infallible, error::Error, fmt, num::ParseIntError};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: Option<usize>,
+    pub column: usize,
+    pub text: String,
+    pub expected: String,
+}
+
+impl ParseError {
+    pub fn new(column: usize, text: &str, expected: &str) -> Self {
+        Self {
+            line: None,
+            column,
+            text: text.to_string(),
+            expected: expected.to_string(),
+        }
+    }
+
+    pub fn at(source: &str, fragment: &str, expected: &str) -> Self {
+        Self::new(column_of(source, fragment), fragment, expected)
+    }
+
+    pub fn with_line(mut self, line: usize, line_text: &str) -> Self {
+        self.line = Some(line);
+
+        if self.text.is_empty() && self.column <= 1 {
+            self.text = line_text.to_string();
+        }
+
+        self
+    }
+}
+
+fn column_of(source: &str, fragment: &str) -> usize {
+    let source_start = source.as_ptr() as usize;
+    let fragment_start = fragment.as_ptr() as usize;
+
+    if fragment_start < source_start || fragment_start > source_start + source.len() {
+        return 1;
+    }
+
+    source[..fragment_start - source_start].chars().count() + 1
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(line) = self.line {
+            write!(f, "line {}, ", line)?;
+        }
+
+        write!(
+            f,
+            "column {}: expected {}, found '{}'",
+            self.column, self.expected, self.text
+        )
+    }
+}
+
+impl Error for ParseError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SolveError {
+    Overflow(String),
+}
+
+impl fmt::Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SolveError::Overflow(message) => write!(f, "overflow: {}", message),
+        }
+    }
+}
+
+impl Error for SolveError {}
+
+impl From<ParseIntError> for ParseError {
+    fn from(_: ParseIntError) -> Self {
+        ParseError::new(1, "", "an integer")
+    }
+}
+
+impl From<ParseBigIntError> for ParseError {
+    fn from(_: ParseBigIntError) -> Self {
+        ParseError::new(1, "", "an integer")
+    }
+}
+
+impl From<Infallible> for ParseError {
+    fn from(error: Infallible) -> Self {
+        match error {}
+    }
+}
+
+#[test]
+fn test_at() {
+    let line = "1-3 a: abcde";
+    let error = ParseError::at(line, &line[4..5], "a letter");
+
+    assert_eq!(error.column, 5);
+    assert_eq!(error.text, "a");
+    assert_eq!(
+        error.with_line(2, line).to_string(),
+        "line 2, column 5: expected a letter, found 'a'"
+    );
+}
+
+#[test]
+fn test_with_line_fills_text() {
+    let error = ParseError::from("x".parse::<i32>().unwrap_err()).with_line(7, "x");
+
+    assert_eq!(error.text, "x");
+    assert_eq!(
+        error.to_string(),
+        "line 7, column 1: expected an integer, found 'x'"
+    );
+}
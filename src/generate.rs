@@ -0,0 +1,79 @@
+use rand::{seq::SliceRandom, Rng as _, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+pub type Rng = ChaCha8Rng;
+
+pub const DEFAULT_SEED: u64 = 2020;
+pub const DEFAULT_SIZE: usize = 1000;
+
+pub fn rng(seed: u64) -> Rng {
+    Rng::seed_from_u64(seed)
+}
+
+pub fn lines<F: FnMut(&mut Rng) -> String>(rng: &mut Rng, count: usize, mut line: F) -> String {
+    (0..count).map(|_| line(rng) + "\n").collect()
+}
+
+pub fn chars(rng: &mut Rng, len: usize, alphabet: &[char]) -> String {
+    (0..len)
+        .map(|_| *alphabet.choose(rng).unwrap_or(&' '))
+        .collect()
+}
+
+pub fn distinct<T>(rng: &mut Rng, mut values: Vec<T>, count: usize) -> Vec<T> {
+    values.shuffle(rng);
+    values.truncate(count);
+    values
+}
+
+pub fn weighted(rng: &mut Rng, probability: f64) -> bool {
+    rng.gen_bool(probability.clamp(0.0, 1.0))
+}
+
+#[test]
+fn test_rng_is_seeded() {
+    let sample = |seed| lines(&mut rng(seed), 3, |rng| chars(rng, 8, &['a', 'b', 'c']));
+
+    assert_eq!(sample(1), sample(1));
+    assert_ne!(sample(1), sample(2));
+    assert_eq!(sample(1).lines().count(), 3);
+}
+
+#[test]
+fn test_generated_inputs_parse() {
+    let registry = crate::registry();
+
+    for day_num in registry.days() {
+        let text = match registry.generate(day_num, &mut rng(day_num as u64), 20) {
+            Some(text) => text,
+            None => continue,
+        };
+        let input = crate::input::Input::new(text.as_str());
+
+        assert!(
+            registry.parse(day_num, &input).unwrap().is_ok(),
+            "day {} generated unparsable input:\n{}",
+            day_num,
+            text
+        );
+
+        let runs = crate::runner::run_parts_with_timeout(
+            &registry,
+            day_num,
+            &crate::runner::PART_NUMS,
+            input,
+            Some(std::time::Duration::from_secs(1)),
+        );
+
+        for run in runs {
+            assert_ne!(
+                run.answer,
+                Err(crate::runner::RunError::Panicked),
+                "day {} part {} panicked on generated input:\n{}",
+                day_num,
+                run.part_num,
+                text
+            );
+        }
+    }
+}
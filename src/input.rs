@@ -0,0 +1,215 @@
+use crate::error::ParseError;
+use std::{
+    cell::RefCell,
+    collections::BTreeMap,
+    fs,
+    io::{self, Read},
+    path::PathBuf,
+    str::FromStr,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Source {
+    Stdin,
+    File(PathBuf),
+    Text(String),
+}
+
+impl Source {
+    pub fn read(&self) -> io::Result<String> {
+        match self {
+            Source::Stdin => {
+                let mut text = String::new();
+                io::stdin().read_to_string(&mut text)?;
+                Ok(text)
+            }
+            Source::File(path) => fs::read_to_string(path),
+            Source::Text(text) => Ok(text.clone()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ErrorPolicy {
+    #[default]
+    FailFast,
+    Collect,
+    SkipWithWarning,
+}
+
+impl FromStr for ErrorPolicy {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "fail-fast" => Ok(ErrorPolicy::FailFast),
+            "collect" => Ok(ErrorPolicy::Collect),
+            "skip" => Ok(ErrorPolicy::SkipWithWarning),
+            _ => Err(ParseError::at(s, s, "'fail-fast', 'collect' or 'skip'")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Report {
+    pub errors: Vec<ParseError>,
+}
+
+impl Report {
+    pub fn is_empty(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    pub fn failed_lines(&self) -> Vec<usize> {
+        self.errors.iter().filter_map(|error| error.line).collect()
+    }
+}
+
+#[derive(Debug)]
+pub struct Input {
+    text: String,
+    policy: ErrorPolicy,
+    params: BTreeMap<String, String>,
+    report: RefCell<Report>,
+}
+
+impl Input {
+    pub fn new<S: Into<String>>(text: S) -> Self {
+        Self {
+            text: text.into(),
+            policy: ErrorPolicy::default(),
+            params: BTreeMap::new(),
+            report: RefCell::new(Report::default()),
+        }
+    }
+
+    pub fn read(source: &Source) -> io::Result<Self> {
+        Ok(Self::new(source.read()?))
+    }
+
+    pub fn with_policy(mut self, policy: ErrorPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    pub fn with_param(mut self, name: &str, value: &str) -> Self {
+        self.params.insert(name.to_string(), value.to_string());
+        self
+    }
+
+    pub fn param<T>(&self, name: &str, default: T) -> Result<T, ParseError>
+    where
+        T: FromStr,
+        T::Err: Into<ParseError>,
+    {
+        self.param_with(name, default, |value| {
+            value.parse::<T>().map_err(Into::into)
+        })
+    }
+
+    pub fn param_with<T, F>(&self, name: &str, default: T, parse: F) -> Result<T, ParseError>
+    where
+        F: FnOnce(&str) -> Result<T, ParseError>,
+    {
+        match self.params.get(name) {
+            Some(value) => parse(value).map_err(|mut error| {
+                error.column += name.len() + 1;
+                error.expected = format!("{} for parameter '{}'", error.expected, name);
+                if error.text.is_empty() {
+                    error.text = value.clone();
+                }
+                error
+            }),
+            None => Ok(default),
+        }
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn policy(&self) -> ErrorPolicy {
+        self.policy
+    }
+
+    pub fn report(&self) -> Report {
+        self.report.borrow().clone()
+    }
+
+    pub fn reject(&self, error: ParseError) -> Result<(), ParseError> {
+        self.report.borrow_mut().errors.push(error.clone());
+
+        match self.policy {
+            ErrorPolicy::FailFast => Err(error),
+            ErrorPolicy::Collect => Ok(()),
+            ErrorPolicy::SkipWithWarning => {
+                crate::warn!("Skipping input, {}", error);
+                Ok(())
+            }
+        }
+    }
+
+    pub fn finish<T>(&self, value: T) -> Result<T, ParseError> {
+        match (self.policy, self.report.borrow().errors.first()) {
+            (ErrorPolicy::Collect, Some(error)) => Err(error.clone()),
+            _ => Ok(value),
+        }
+    }
+
+    pub fn lines<T>(&self) -> Result<Vec<T>, ParseError>
+    where
+        T: FromStr,
+        T::Err: Into<ParseError>,
+    {
+        let mut list: Vec<T> = vec![];
+
+        for (line_index, line) in self.text.lines().enumerate() {
+            match line.parse::<T>() {
+                Ok(item) => list.push(item),
+                Err(err) => self.reject(err.into().with_line(line_index + 1, line))?,
+            }
+        }
+
+        self.finish(list)
+    }
+}
+
+#[test]
+fn test_param() {
+    let input = Input::new("")
+        .with_param("target", "99")
+        .with_param("turns", "x");
+
+    assert_eq!(input.param("target", 2020), Ok(99));
+    assert_eq!(input.param("preamble", 25), Ok(25));
+
+    let error = input.param("turns", 2020).unwrap_err();
+    assert_eq!(error.column, 7);
+    assert_eq!(error.text, "x");
+}
+
+#[test]
+fn test_lines_fail_fast() {
+    let input = Input::new("1\nx\n3\ny\n");
+
+    let error = input.lines::<i32>().unwrap_err();
+    assert_eq!(error.line, Some(2));
+    assert_eq!(input.report().failed_lines(), vec![2]);
+}
+
+#[test]
+fn test_lines_collect() {
+    let input = Input::new("1\nx\n3\ny\n").with_policy(ErrorPolicy::Collect);
+
+    let error = input.lines::<i32>().unwrap_err();
+    assert_eq!(error.line, Some(2));
+    assert_eq!(input.report().failed_lines(), vec![2, 4]);
+}
+
+#[test]
+fn test_lines_skip_with_warning() {
+    let input = Input::new("1\nx\n3\ny\n").with_policy(ErrorPolicy::SkipWithWarning);
+
+    assert_eq!(input.lines::<i32>().unwrap(), vec![1, 3]);
+    assert_eq!(input.report().failed_lines(), vec![2, 4]);
+}
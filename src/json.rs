@@ -0,0 +1,53 @@
+use crate::{answer::Answer, error::ParseError, runner::PartRun};
+use serde_json::{json, Value};
+use std::time::Duration;
+
+pub fn answer(answer: &Answer) -> Value {
+    match answer {
+        Answer::Integer(value) => json!(value),
+        Answer::BigInteger(value) => json!(value.to_string()),
+        Answer::Text(value) => json!(value),
+    }
+}
+
+pub fn parse_error(error: &ParseError) -> Value {
+    json!({
+        "line": error.line,
+        "column": error.column,
+        "text": error.text,
+        "expected": error.expected,
+    })
+}
+
+pub fn duration(duration: Duration) -> Value {
+    json!(duration.as_secs_f64() * 1000.0)
+}
+
+pub fn part_run(run: &PartRun) -> Value {
+    json!({
+        "day": run.day_num,
+        "part": run.part_num,
+        "answer": run.answer.as_ref().ok().map(answer),
+        "error": run.answer.as_ref().err().map(ToString::to_string),
+        "diagnostics": run.diagnostics.iter().map(parse_error).collect::<Vec<Value>>(),
+        "parse_ms": duration(run.parse_time),
+        "solve_ms": duration(run.solve_time),
+    })
+}
+
+#[test]
+fn test_part_run() {
+    let run = PartRun {
+        day_num: 1,
+        part_num: 2,
+        answer: Ok(Answer::from(241861950)),
+        diagnostics: vec![ParseError::new(1, "x", "an integer").with_line(3, "x")],
+        parse_time: Duration::from_millis(2),
+        solve_time: Duration::default(),
+    };
+
+    assert_eq!(
+        part_run(&run).to_string(),
+        r#"{"answer":241861950,"day":1,"diagnostics":[{"column":1,"expected":"an integer","line":3,"text":"x"}],"error":null,"parse_ms":2.0,"part":2,"solve_ms":0.0}"#
+    );
+}
@@ -0,0 +1,54 @@
+use solver::Registry;
+
+pub mod answer;
+pub mod answers;
+pub mod bench;
+pub mod cancel;
+pub mod day1;
+pub mod day10;
+pub mod day11;
+pub mod day12;
+pub mod day13;
+pub mod day14;
+pub mod day15;
+pub mod day2;
+pub mod day3;
+pub mod day4;
+pub mod day5;
+pub mod day6;
+pub mod day7;
+pub mod day8;
+pub mod day9;
+pub mod error;
+pub mod generate;
+pub mod input;
+pub mod json;
+pub mod log;
+pub mod repl;
+pub mod runner;
+pub mod scaffold;
+pub mod server;
+pub mod solver;
+pub mod utils;
+
+pub fn registry() -> Registry {
+    let mut registry = Registry::new();
+
+    day1::register(&mut registry);
+    day2::register(&mut registry);
+    day3::register(&mut registry);
+    day4::register(&mut registry);
+    day5::register(&mut registry);
+    day6::register(&mut registry);
+    day7::register(&mut registry);
+    day8::register(&mut registry);
+    day9::register(&mut registry);
+    day10::register(&mut registry);
+    day11::register(&mut registry);
+    day12::register(&mut registry);
+    day13::register(&mut registry);
+    day14::register(&mut registry);
+    day15::register(&mut registry);
+
+    registry
+}
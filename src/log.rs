@@ -0,0 +1,75 @@
+use std::sync::atomic::{AtomicU8, Ordering};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub enum Verbosity {
+    Quiet,
+    #[default]
+    Normal,
+    Verbose,
+    Trace,
+}
+
+static VERBOSITY: AtomicU8 = AtomicU8::new(Verbosity::Normal as u8);
+
+impl Verbosity {
+    pub fn from_flag(flag: &str) -> Option<Self> {
+        match flag {
+            "-q" | "--quiet" => Some(Verbosity::Quiet),
+            "-v" | "--verbose" => Some(Verbosity::Verbose),
+            "-vv" => Some(Verbosity::Trace),
+            _ => None,
+        }
+    }
+}
+
+pub fn set_verbosity(verbosity: Verbosity) {
+    VERBOSITY.store(verbosity as u8, Ordering::Relaxed);
+}
+
+pub fn verbosity() -> Verbosity {
+    match VERBOSITY.load(Ordering::Relaxed) {
+        0 => Verbosity::Quiet,
+        1 => Verbosity::Normal,
+        2 => Verbosity::Verbose,
+        _ => Verbosity::Trace,
+    }
+}
+
+pub fn enabled(verbosity: Verbosity) -> bool {
+    verbosity <= self::verbosity()
+}
+
+#[macro_export]
+macro_rules! warn {
+    ($($arg:tt)*) => {
+        if $crate::log::enabled($crate::log::Verbosity::Normal) {
+            eprintln!($($arg)*);
+        }
+    };
+}
+
+#[macro_export]
+macro_rules! debug {
+    ($($arg:tt)*) => {
+        if $crate::log::enabled($crate::log::Verbosity::Verbose) {
+            eprintln!($($arg)*);
+        }
+    };
+}
+
+#[macro_export]
+macro_rules! trace {
+    ($($arg:tt)*) => {
+        if $crate::log::enabled($crate::log::Verbosity::Trace) {
+            eprintln!($($arg)*);
+        }
+    };
+}
+
+#[test]
+fn test_from_flag() {
+    assert_eq!(Verbosity::from_flag("-q"), Some(Verbosity::Quiet));
+    assert_eq!(Verbosity::from_flag("-vv"), Some(Verbosity::Trace));
+    assert_eq!(Verbosity::from_flag("-vvv"), None);
+    assert!(Verbosity::Quiet < Verbosity::Normal);
+}
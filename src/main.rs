@@ -1,78 +1,662 @@
-use std::{env, process};
-
-mod day1;
-mod day10;
-mod day11;
-mod day12;
-mod day13;
-mod day14;
-mod day15;
-mod day2;
-mod day3;
-mod day4;
-mod day5;
-mod day6;
-mod day7;
-mod day8;
-mod day9;
-mod utils;
-
-fn help() {
+use aoc2020::{
+    answers::{self, Answers, Verdict},
+    bench::{self, Baseline, Comparison, Stats},
+    error::ParseError,
+    generate,
+    input::{ErrorPolicy, Input, Source},
+    json,
+    log::{self, Verbosity},
+    repl,
+    runner::{self, PartRun, RunError},
+    scaffold, server,
+    solver::Registry,
+};
+use serde_json::json;
+use std::{
+    env, fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    process,
+    time::Duration,
+};
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Text,
+    Json,
+}
+
+impl Format {
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "text" => Some(Format::Text),
+            "json" => Some(Format::Json),
+            _ => None,
+        }
+    }
+}
+
+struct Options {
+    positional: Vec<String>,
+    on_error: ErrorPolicy,
+    input: Option<PathBuf>,
+    answers: PathBuf,
+    verbosity: Verbosity,
+    format: Format,
+    params: Vec<(String, String)>,
+    warmup: usize,
+    iterations: usize,
+    baseline: Option<PathBuf>,
+    save_baseline: Option<PathBuf>,
+    tolerance: f64,
+    jobs: usize,
+    timeout: Option<Duration>,
+    port: u16,
+    size: usize,
+    seed: u64,
+}
+
+fn parse_options(args: &[String]) -> Option<Options> {
+    let mut options = Options {
+        positional: vec![],
+        on_error: ErrorPolicy::default(),
+        input: None,
+        answers: answers::answers_path(),
+        verbosity: Verbosity::default(),
+        format: Format::Text,
+        params: vec![],
+        warmup: bench::DEFAULT_WARMUP,
+        iterations: bench::DEFAULT_ITERATIONS,
+        baseline: None,
+        save_baseline: None,
+        tolerance: bench::DEFAULT_TOLERANCE,
+        jobs: 1,
+        timeout: None,
+        port: server::DEFAULT_PORT,
+        size: generate::DEFAULT_SIZE,
+        seed: generate::DEFAULT_SEED,
+    };
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--on-error" => options.on_error = args.next()?.parse().ok()?,
+            "--input" => options.input = Some(PathBuf::from(args.next()?)),
+            "--answers" => options.answers = PathBuf::from(args.next()?),
+            "--format" => options.format = Format::from_name(args.next()?)?,
+            "--warmup" => options.warmup = args.next()?.parse().ok()?,
+            "--iterations" => options.iterations = args.next()?.parse().ok().filter(|n| *n > 0)?,
+            "--baseline" => options.baseline = Some(PathBuf::from(args.next()?)),
+            "--save-baseline" => options.save_baseline = Some(PathBuf::from(args.next()?)),
+            "--tolerance" => options.tolerance = args.next()?.parse().ok()?,
+            "--port" => options.port = args.next()?.parse().ok()?,
+            "--size" => options.size = args.next()?.parse().ok()?,
+            "--seed" => options.seed = args.next()?.parse().ok()?,
+            "--jobs" => options.jobs = args.next()?.parse().ok().filter(|n| *n > 0)?,
+            "--timeout" => {
+                let secs = args
+                    .next()?
+                    .parse::<f64>()
+                    .ok()
+                    .filter(|secs| *secs > 0.0)?;
+                options.timeout = Some(Duration::from_secs_f64(secs));
+            }
+            "--param" => {
+                let (name, value) = args.next()?.split_once('=')?;
+                options.params.push((name.to_string(), value.to_string()));
+            }
+            flag if Verbosity::from_flag(flag).is_some() => {
+                options.verbosity = Verbosity::from_flag(flag)?
+            }
+            _ => options.positional.push(arg.clone()),
+        }
+    }
+
+    Some(options)
+}
+
+fn parse_day_nums(registry: &Registry, args: &[String]) -> Option<Vec<u8>> {
+    if args.is_empty() {
+        return Some(registry.days());
+    }
+
+    args.iter()
+        .map(|arg| {
+            arg.parse::<u8>()
+                .ok()
+                .filter(|day_num| registry.contains(*day_num))
+        })
+        .collect()
+}
+
+fn help(registry: &Registry) {
+    let days = registry.days();
+
+    println!(
+        "Usage: ./aoc2020 <day_num> <part_num> [--input <path>] [<options>]
+       ./aoc2020 all [<day_num>...] [--jobs <n>] [<options>]
+       ./aoc2020 verify [<day_num>...] [--answers <path>] [--jobs <n>] [<options>]
+       ./aoc2020 params [<day_num>...]
+       ./aoc2020 serve [--port <port>] [--timeout <secs>]
+       ./aoc2020 repl [--timeout <secs>]
+       ./aoc2020 generate <day_num> [--size <n>] [--seed <n>]
+       ./aoc2020 new-day <day_num>
+       ./aoc2020 bench <day_num> <part_num> [--warmup <n>] [--iterations <n>]
+            [--baseline <path>] [--save-baseline <path>] [--tolerance <percent>] [<options>]
+        where <day_num>: [{}..{}], <part_num>: [1..2]
+        input is read from --input (- for stdin or a pipe), stdin redirected from a non-empty file
+            or data/day<day_num>.txt, in that order
+        <options>: [--on-error fail-fast|collect|skip] [--format text|json] [--param <name>=<value>]...
+            [--timeout <secs>] [-q|-v|-vv]
+        all, verify, serve and repl stop each solve after --timeout or {}s; verify counts a timeout as a failure",
+        days.first().unwrap_or(&0),
+        days.last().unwrap_or(&0),
+        runner::DEFAULT_TIMEOUT.as_secs()
+    );
+}
+
+fn format_duration(duration: Duration) -> String {
+    format!("{:.3}ms", duration.as_secs_f64() * 1000.0)
+}
+
+fn print_table(runs: &[PartRun]) {
+    let answers = runs
+        .iter()
+        .map(|run| match &run.answer {
+            Ok(answer) => answer.to_string(),
+            Err(err) => format!("({})", err),
+        })
+        .collect::<Vec<String>>();
+
+    let answer_width = answers.iter().map(String::len).max().unwrap_or(0).max(6);
+
+    println!(
+        "{:>3} {:>4}  {:<width$}  {:>12}  {:>12}",
+        "Day",
+        "Part",
+        "Answer",
+        "Parse",
+        "Solve",
+        width = answer_width
+    );
+
+    for (run, answer) in runs.iter().zip(&answers) {
+        println!(
+            "{:>3} {:>4}  {:<width$}  {:>12}  {:>12}",
+            run.day_num,
+            run.part_num,
+            answer,
+            format_duration(run.parse_time),
+            format_duration(run.solve_time),
+            width = answer_width
+        );
+    }
+
+    let total_parse_time = runs
+        .iter()
+        .filter(|run| run.part_num == runner::PART_NUMS[0])
+        .map(|run| run.parse_time)
+        .sum::<Duration>();
+    let total_solve_time = runs.iter().map(|run| run.solve_time).sum::<Duration>();
+
     println!(
-        "Usage: ./aoc2020 <day_num> <part_num> < data/day<day_num>.txt
-        where <day_num>: [1..15], <part_num>: [1..2]"
+        "{:<8}  {:<width$}  {:>12}  {:>12}",
+        "Total",
+        format_duration(total_parse_time + total_solve_time),
+        format_duration(total_parse_time),
+        format_duration(total_solve_time),
+        width = answer_width
     );
 }
 
+#[cfg(unix)]
+fn stdin_is_file() -> bool {
+    use std::os::fd::AsFd;
+
+    io::stdin()
+        .as_fd()
+        .try_clone_to_owned()
+        .map(fs::File::from)
+        .and_then(|file| file.metadata())
+        .is_ok_and(|metadata| metadata.is_file())
+}
+
+#[cfg(not(unix))]
+fn stdin_is_file() -> bool {
+    false
+}
+
+fn input_source(options: &Options, day_num: u8) -> io::Result<Source> {
+    match &options.input {
+        Some(path) if path.as_os_str() == "-" => Ok(Source::Stdin),
+        Some(path) => Ok(Source::File(path.clone())),
+        None if stdin_is_file() => match Source::Stdin.read()? {
+            text if text.trim().is_empty() => Ok(Source::File(runner::data_path(day_num))),
+            text => Ok(Source::Text(text)),
+        },
+        None => Ok(Source::File(runner::data_path(day_num))),
+    }
+}
+
+fn check_params(registry: &Registry, options: &Options, day_nums: &[u8]) {
+    for (name, _) in &options.params {
+        let declared = day_nums.iter().any(|day_num| {
+            registry
+                .params(*day_num)
+                .iter()
+                .any(|param| param.name == name)
+        });
+
+        if !declared {
+            eprintln!("Unknown parameter '{}'", name);
+            print_params(registry, day_nums);
+            process::exit(1);
+        }
+    }
+}
+
+fn print_params(registry: &Registry, day_nums: &[u8]) {
+    for day_num in day_nums {
+        let params = registry
+            .params(*day_num)
+            .iter()
+            .map(|param| format!("{}={}", param.name, param.default))
+            .collect::<Vec<String>>();
+
+        if !params.is_empty() {
+            println!("Day {:>2}: {}", day_num, params.join("  "));
+        }
+    }
+}
+
+fn read_input(
+    source: &Source,
+    on_error: ErrorPolicy,
+    params: &[(String, String)],
+) -> io::Result<Input> {
+    let input = Input::read(source)?.with_policy(on_error);
+
+    Ok(params
+        .iter()
+        .fold(input, |input, (name, value)| input.with_param(name, value)))
+}
+
+fn run_days(registry: &Registry, options: &Options, day_nums: &[u8]) -> Vec<PartRun> {
+    let on_error = options.on_error;
+    let params = options.params.clone();
+
+    let load_input = move |day_num| {
+        let path = runner::data_path(day_num);
+
+        read_input(&Source::File(path.clone()), on_error, &params)
+            .map_err(|err| format!("{}: {}", path.display(), err))
+    };
+
+    runner::run_days_parallel(
+        registry,
+        day_nums,
+        options.jobs,
+        Some(options.timeout.unwrap_or(runner::DEFAULT_TIMEOUT)),
+        load_input,
+    )
+}
+
+fn print_json_lines(runs: &[PartRun]) {
+    for run in runs {
+        println!("{}", json::part_run(run));
+    }
+}
+
+fn verify(registry: &Registry, options: &Options, day_nums: &[u8]) -> bool {
+    let answers = fs::read_to_string(&options.answers)
+        .map_err(|err| err.to_string())
+        .and_then(|text| Answers::parse(&text).map_err(|err| err.to_string()))
+        .unwrap_or_else(|err| {
+            eprintln!("Could not load {}: {}", options.answers.display(), err);
+            process::exit(1);
+        });
+
+    let (mut passed, mut failed, mut missing) = (0, 0, 0);
+
+    for run in run_days(registry, options, day_nums) {
+        let actual = match &run.answer {
+            Ok(answer) => answer.to_string(),
+            Err(err) => format!("({})", err),
+        };
+
+        let (verdict, status) = match answers.verify(&run) {
+            Verdict::Pass => {
+                passed += 1;
+                ("pass", format!("PASS     {}", actual))
+            }
+            Verdict::Fail { expected } => {
+                failed += 1;
+                (
+                    "fail",
+                    format!("FAIL     expected {}, got {}", expected, actual),
+                )
+            }
+            Verdict::Missing => {
+                missing += 1;
+                ("missing", format!("MISSING  got {}", actual))
+            }
+            Verdict::TimedOut => {
+                failed += 1;
+                ("timeout", format!("TIMEOUT  {}", actual))
+            }
+        };
+
+        match options.format {
+            Format::Text => println!("Day {:>2} part {}: {}", run.day_num, run.part_num, status),
+            Format::Json => {
+                let mut value = json::part_run(&run);
+                value["verdict"] = json!(verdict);
+                value["expected"] = json!(answers.get(run.day_num, run.part_num));
+                println!("{}", value);
+            }
+        }
+    }
+
+    if options.format == Format::Text {
+        println!(
+            "Passed: {}, failed: {}, missing: {}",
+            passed, failed, missing
+        );
+    }
+
+    failed == 0
+}
+
+fn parse_day_part(registry: &Registry, args: &[String]) -> Option<(u8, u8)> {
+    if args.len() != 2 {
+        return None;
+    }
+
+    let day_num = args[0].parse::<u8>().ok()?;
+    let part_num = args[1].parse::<u8>().ok()?;
+
+    if !registry.contains(day_num) || !runner::PART_NUMS.contains(&part_num) {
+        return None;
+    }
+
+    Some((day_num, part_num))
+}
+
+fn exit_with_parse_errors(diagnostics: &[ParseError]) -> ! {
+    for error in diagnostics {
+        eprintln!("Parse error: {}", error);
+    }
+    process::exit(1);
+}
+
+fn bench(registry: &Registry, options: &Options, day_num: u8, part_num: u8, input: &Input) -> bool {
+    let solver = match registry.parse(day_num, input) {
+        Some(Ok(solver)) => solver,
+        Some(Err(err)) => exit_with_parse_errors(&runner::diagnostics(input, Some(&err))),
+        None => unreachable!("day {} is registered", day_num),
+    };
+
+    let (answer, samples) = bench::run(&*solver, part_num, options.warmup, options.iterations);
+    let error = answer.as_ref().err().map(ToString::to_string);
+    let answer = answer.ok().flatten();
+    let stats = Stats::from_samples(&samples).expect("at least one iteration");
+
+    let baseline = options.baseline.as_ref().map(|path| {
+        fs::read_to_string(path)
+            .map_err(|err| err.to_string())
+            .and_then(|text| Baseline::parse(&text).map_err(|err| err.to_string()))
+            .unwrap_or_else(|err| {
+                eprintln!("Could not load {}: {}", path.display(), err);
+                process::exit(1);
+            })
+    });
+
+    let comparison = baseline
+        .as_ref()
+        .and_then(|baseline| baseline.compare(day_num, part_num, &stats, options.tolerance));
+
+    match options.format {
+        Format::Text => {
+            match (&answer, &error) {
+                (_, Some(error)) => eprintln!("Day {} part {}: {}", day_num, part_num, error),
+                (Some(answer), None) => println!("Day {} part {}: {}", day_num, part_num, answer),
+                (None, None) => println!("Day {} part {}: no answer found", day_num, part_num),
+            }
+
+            println!(
+                "{} iterations after {} warm-up: min {}, median {}, mean {}, stddev {}",
+                stats.iterations,
+                options.warmup,
+                format_duration(stats.min),
+                format_duration(stats.median),
+                format_duration(stats.mean),
+                format_duration(stats.stddev)
+            );
+
+            match comparison {
+                Some(Comparison::Faster(change)) => {
+                    println!("Faster than baseline: {:+.1}%", change)
+                }
+                Some(Comparison::Unchanged(change)) => {
+                    println!("Within {}% of baseline: {:+.1}%", options.tolerance, change)
+                }
+                Some(Comparison::Regressed(change)) => {
+                    println!("REGRESSION against baseline: {:+.1}%", change)
+                }
+                None if baseline.is_some() => {
+                    println!("No baseline for day {} part {}", day_num, part_num)
+                }
+                None => (),
+            }
+        }
+        Format::Json => {
+            let (status, change) = match comparison {
+                Some(Comparison::Faster(change)) => (Some("faster"), Some(change)),
+                Some(Comparison::Unchanged(change)) => (Some("unchanged"), Some(change)),
+                Some(Comparison::Regressed(change)) => (Some("regressed"), Some(change)),
+                None => (None, None),
+            };
+
+            println!(
+                "{}",
+                json!({
+                    "day": day_num,
+                    "part": part_num,
+                    "answer": answer.as_ref().map(json::answer),
+                    "error": error,
+                    "iterations": stats.iterations,
+                    "warmup": options.warmup,
+                    "min_ms": json::duration(stats.min),
+                    "median_ms": json::duration(stats.median),
+                    "mean_ms": json::duration(stats.mean),
+                    "stddev_ms": json::duration(stats.stddev),
+                    "baseline": status,
+                    "baseline_change": change,
+                })
+            );
+        }
+    }
+
+    if let Some(path) = &options.save_baseline {
+        let mut saved = match fs::read_to_string(path) {
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Baseline::default()),
+            text => text
+                .map_err(|err| err.to_string())
+                .and_then(|text| Baseline::parse(&text).map_err(|err| err.to_string())),
+        }
+        .unwrap_or_else(|err| {
+            eprintln!("Could not load {}: {}", path.display(), err);
+            process::exit(1);
+        });
+        saved.insert(day_num, part_num, stats.median);
+
+        if let Err(err) = fs::write(path, saved.to_toml()) {
+            eprintln!("Could not write {}: {}", path.display(), err);
+            process::exit(1);
+        }
+    }
+
+    error.is_none() && !matches!(comparison, Some(Comparison::Regressed(_)))
+}
+
 fn main() {
-    let args: Vec<String> = env::args().collect();
+    let registry = aoc2020::registry();
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    let options = parse_options(&args).unwrap_or_else(|| {
+        help(&registry);
+        process::exit(1);
+    });
+
+    log::set_verbosity(options.verbosity);
+
+    let command = options.positional.first().map(String::as_str);
+    if command == Some("all") || command == Some("verify") || command == Some("params") {
+        let day_nums = parse_day_nums(&registry, &options.positional[1..]).unwrap_or_else(|| {
+            help(&registry);
+            process::exit(1);
+        });
 
-    if args.len() != 3 {
-        help();
+        if command == Some("params") {
+            print_params(&registry, &day_nums);
+            return;
+        }
+
+        check_params(&registry, &options, &day_nums);
+
+        if command == Some("verify") {
+            if !verify(&registry, &options, &day_nums) {
+                process::exit(1);
+            }
+            return;
+        }
+
+        let runs = run_days(&registry, &options, &day_nums);
+        match options.format {
+            Format::Text => print_table(&runs),
+            Format::Json => print_json_lines(&runs),
+        }
+        return;
+    }
+
+    if options.positional.is_empty() {
+        help(&registry);
+        return;
+    }
+
+    if command == Some("new-day") && options.positional.len() == 2 {
+        let day_num = options.positional[1]
+            .parse::<u8>()
+            .ok()
+            .filter(|day_num| *day_num > 0 && !registry.contains(*day_num))
+            .unwrap_or_else(|| {
+                eprintln!("Expected an unregistered <day_num>");
+                process::exit(1);
+            });
+
+        match scaffold::new_day(Path::new(env!("CARGO_MANIFEST_DIR")), day_num) {
+            Ok(paths) => paths
+                .iter()
+                .for_each(|path| println!("Wrote {}", path.display())),
+            Err(err) => {
+                eprintln!("Could not create day {}: {}", day_num, err);
+                process::exit(1);
+            }
+        }
+        return;
+    }
+
+    if command == Some("repl") && options.positional.len() == 1 {
+        let timeout = options.timeout.unwrap_or(runner::DEFAULT_TIMEOUT);
+        if let Err(err) = repl::run(registry, timeout) {
+            eprintln!("Could not read input: {}", err);
+            process::exit(1);
+        }
+        return;
+    }
+
+    if command == Some("generate") && options.positional.len() == 2 {
+        let day_num = parse_day_nums(&registry, &options.positional[1..])
+            .unwrap_or_else(|| {
+                help(&registry);
+                process::exit(1);
+            })
+            .remove(0);
+
+        let mut rng = generate::rng(options.seed);
+        let text = registry
+            .generate(day_num, &mut rng, options.size)
+            .unwrap_or_else(|| {
+                eprintln!("Day {} has no input generator", day_num);
+                process::exit(1);
+            });
+
+        if let Err(err) = io::stdout().lock().write_all(text.as_bytes()) {
+            eprintln!("Could not write input: {}", err);
+            process::exit(1);
+        }
+        return;
+    }
+
+    if command == Some("serve") && options.positional.len() == 1 {
+        let timeout = options.timeout.unwrap_or(runner::DEFAULT_TIMEOUT);
+        if let Err(err) = server::serve(&registry, options.port, timeout) {
+            eprintln!("Could not serve on port {}: {}", options.port, err);
+            process::exit(1);
+        }
         return;
     }
 
-    let day_num = &args[1].parse::<u8>().unwrap_or_else(|_| {
-        help();
+    let day_part_args = match command {
+        Some("bench") => &options.positional[1..],
+        _ => &options.positional[..],
+    };
+
+    let (day_num, part_num) = parse_day_part(&registry, day_part_args).unwrap_or_else(|| {
+        help(&registry);
         process::exit(1);
     });
 
-    let part_num = &args[2].parse::<u8>().unwrap_or_else(|_| {
-        help();
+    check_params(&registry, &options, &[day_num]);
+
+    let source = input_source(&options, day_num).unwrap_or_else(|err| {
+        eprintln!("Could not read input: {}", err);
+        process::exit(1);
+    });
+    let input = read_input(&source, options.on_error, &options.params).unwrap_or_else(|err| {
+        match &source {
+            Source::File(path) => eprintln!("Could not read {}: {}", path.display(), err),
+            _ => eprintln!("Could not read input: {}", err),
+        }
         process::exit(1);
     });
 
-    match (day_num, part_num) {
-        (1, 1) => day1::part1(),
-        (1, 2) => day1::part2(),
-        (2, 1) => day2::part1(),
-        (2, 2) => day2::part2(),
-        (3, 1) => day3::part1(),
-        (3, 2) => day3::part2(),
-        (4, 1) => day4::part1(),
-        (4, 2) => day4::part2(),
-        (5, 1) => day5::part1(),
-        (5, 2) => day5::part2(),
-        (6, 1) => day6::part1(),
-        (6, 2) => day6::part2(),
-        (7, 1) => day7::part1(),
-        (7, 2) => day7::part2(),
-        (8, 1) => day8::part1(),
-        (8, 2) => day8::part2(),
-        (9, 1) => day9::part1(),
-        (9, 2) => day9::part2(),
-        (10, 1) => day10::part1(),
-        (10, 2) => day10::part2(),
-        (11, 1) => day11::part1(),
-        (11, 2) => day11::part2(),
-        (12, 1) => day12::part1(),
-        (12, 2) => day12::part2(),
-        (13, 1) => day13::part1(),
-        (13, 2) => day13::part2(),
-        (14, 1) => day14::part1(),
-        (14, 2) => day14::part2(),
-        (15, 1) => day15::part1(),
-        (15, 2) => day15::part2(),
-        _ => help(),
+    if command == Some("bench") {
+        if !bench(&registry, &options, day_num, part_num, &input) {
+            process::exit(1);
+        }
+        return;
+    }
+
+    let run =
+        runner::run_parts_with_timeout(&registry, day_num, &[part_num], input, options.timeout)
+            .remove(0);
+
+    if options.format == Format::Json {
+        println!("{}", json::part_run(&run));
+        if !matches!(run.answer, Ok(_) | Err(RunError::NoAnswer)) {
+            process::exit(1);
+        }
+        return;
+    }
+
+    match run.answer {
+        Ok(answer) => println!("{}", answer),
+        Err(RunError::Parse(_)) => exit_with_parse_errors(&run.diagnostics),
+        Err(RunError::NoAnswer) => println!("No answer found"),
+        Err(err) => {
+            eprintln!("Day {} part {}: {}", day_num, part_num, err);
+            process::exit(1);
+        }
     }
 }
@@ -0,0 +1,294 @@
+use crate::{
+    input::{Input, Source},
+    runner::{self, RunError},
+    solver::{Registry, Solver},
+};
+use std::{
+    io::{self, BufRead, Write},
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
+const HELP: &str = "Commands:
+  load <day_num> [<path>]    load data/day<day_num>.txt or <path> and parse it
+  params                     list the parameters of the loaded day
+  set <name>=<value>         override a parameter and parse again
+  unset <name>               restore a parameter default and parse again
+  run [<part_num>]           run one or both parts
+  values                     list the values the loaded day can show
+  show <name> [<args>...]    print an intermediate value
+  quit                       leave the repl";
+
+pub struct Session {
+    registry: Registry,
+    day_num: Option<u8>,
+    text: String,
+    params: Vec<(String, String)>,
+    solver: Option<Box<dyn Solver>>,
+    timeout: Duration,
+}
+
+impl Session {
+    pub fn new(registry: Registry, timeout: Duration) -> Self {
+        Self {
+            registry,
+            day_num: None,
+            text: String::new(),
+            params: vec![],
+            solver: None,
+            timeout,
+        }
+    }
+
+    pub fn day_num(&self) -> Option<u8> {
+        self.day_num
+    }
+
+    fn input(&self) -> Input {
+        self.params
+            .iter()
+            .fold(Input::new(self.text.as_str()), |input, (name, value)| {
+                input.with_param(name, value)
+            })
+    }
+
+    fn parse(&mut self) -> Result<String, String> {
+        let day_num = self.day_num.ok_or("no day loaded, use 'load <day_num>'")?;
+        let input = self.input();
+
+        let start = Instant::now();
+        self.solver = None;
+
+        match self.registry.parse(day_num, &input) {
+            Some(Ok(solver)) => {
+                self.solver = Some(solver);
+                Ok(format!(
+                    "Parsed day {} in {:.3}ms",
+                    day_num,
+                    start.elapsed().as_secs_f64() * 1000.0
+                ))
+            }
+            Some(Err(err)) => Err(runner::diagnostics(&input, Some(&err))
+                .iter()
+                .map(|error| format!("Parse error: {}", error))
+                .collect::<Vec<String>>()
+                .join("\n")),
+            None => Err(format!("no solver for day {}", day_num)),
+        }
+    }
+
+    fn solver(&self) -> Result<&dyn Solver, String> {
+        self.solver
+            .as_deref()
+            .ok_or_else(|| "no parsed input, use 'load <day_num>'".to_string())
+    }
+
+    fn load(&mut self, args: &[&str]) -> Result<String, String> {
+        let day_num = args
+            .first()
+            .and_then(|arg| arg.parse::<u8>().ok())
+            .filter(|day_num| self.registry.contains(*day_num))
+            .ok_or("expected a registered <day_num>")?;
+
+        let path = args
+            .get(1)
+            .map_or_else(|| runner::data_path(day_num), PathBuf::from);
+
+        self.text = Source::File(path.clone())
+            .read()
+            .map_err(|err| format!("Could not read {}: {}", path.display(), err))?;
+
+        if self.day_num != Some(day_num) {
+            self.params.clear();
+        }
+        self.day_num = Some(day_num);
+
+        self.parse()
+    }
+
+    fn params(&self) -> Result<String, String> {
+        let day_num = self.day_num.ok_or("no day loaded, use 'load <day_num>'")?;
+
+        Ok(self
+            .registry
+            .params(day_num)
+            .iter()
+            .map(
+                |param| match self.params.iter().find(|(name, _)| name == param.name) {
+                    Some((_, value)) => {
+                        format!("{}={} (default {})", param.name, value, param.default)
+                    }
+                    None => format!("{}={}", param.name, param.default),
+                },
+            )
+            .collect::<Vec<String>>()
+            .join("\n"))
+    }
+
+    fn set(&mut self, args: &[&str]) -> Result<String, String> {
+        let day_num = self.day_num.ok_or("no day loaded, use 'load <day_num>'")?;
+        let assignment = args.join(" ");
+        let (name, value) = assignment
+            .split_once('=')
+            .ok_or("expected <name>=<value>")?;
+
+        if !self
+            .registry
+            .params(day_num)
+            .iter()
+            .any(|param| param.name == name)
+        {
+            return Err(format!("unknown parameter '{}'", name));
+        }
+
+        self.params.retain(|(param_name, _)| param_name != name);
+        self.params.push((name.to_string(), value.to_string()));
+
+        self.parse()
+    }
+
+    fn unset(&mut self, args: &[&str]) -> Result<String, String> {
+        let name = args.first().ok_or("expected <name>")?;
+        self.params.retain(|(param_name, _)| param_name != name);
+
+        self.parse()
+    }
+
+    fn run(&self, args: &[&str]) -> Result<String, String> {
+        self.solver()?;
+        let day_num = self.day_num.ok_or("no day loaded, use 'load <day_num>'")?;
+        let part_nums = match args.first() {
+            Some(arg) => vec![arg
+                .parse::<u8>()
+                .ok()
+                .filter(|part_num| runner::PART_NUMS.contains(part_num))
+                .ok_or("expected <part_num> 1 or 2")?],
+            None => runner::PART_NUMS.to_vec(),
+        };
+
+        let runs = runner::run_parts_with_timeout(
+            &self.registry,
+            day_num,
+            &part_nums,
+            self.input(),
+            Some(self.timeout),
+        );
+
+        Ok(runs
+            .iter()
+            .map(|run| {
+                let elapsed = run.solve_time.as_secs_f64() * 1000.0;
+
+                match &run.answer {
+                    Ok(answer) => Ok(format!(
+                        "Part {}: {} ({:.3}ms)",
+                        run.part_num, answer, elapsed
+                    )),
+                    Err(RunError::NoAnswer) => Ok(format!(
+                        "Part {}: no answer found ({:.3}ms)",
+                        run.part_num, elapsed
+                    )),
+                    Err(err) => Err(format!("Part {}: {}", run.part_num, err)),
+                }
+            })
+            .collect::<Result<Vec<String>, String>>()?
+            .join("\n"))
+    }
+
+    pub fn execute(&mut self, line: &str) -> Result<String, String> {
+        let words = line.split_whitespace().collect::<Vec<&str>>();
+
+        match words.as_slice() {
+            [] => Ok(String::new()),
+            ["help"] => Ok(HELP.to_string()),
+            ["load", args @ ..] => self.load(args),
+            ["params"] => self.params(),
+            ["set", args @ ..] => self.set(args),
+            ["unset", args @ ..] => self.unset(args),
+            ["run", args @ ..] => self.run(args),
+            ["values"] => Ok(self.solver()?.values().join("\n")),
+            ["show", name, args @ ..] => self.solver()?.value(name, args),
+            _ => Err(format!("unknown command '{}', try 'help'", line.trim())),
+        }
+    }
+}
+
+pub fn run(registry: Registry, timeout: Duration) -> io::Result<()> {
+    let mut session = Session::new(registry, timeout);
+    let stdin = io::stdin();
+    let mut lines = stdin.lock().lines();
+
+    loop {
+        match session.day_num() {
+            Some(day_num) => print!("day{}> ", day_num),
+            None => print!("aoc> "),
+        }
+        io::stdout().flush()?;
+
+        let line = match lines.next() {
+            Some(line) => line?,
+            None => break,
+        };
+
+        if matches!(line.trim(), "quit" | "exit") {
+            break;
+        }
+
+        match session.execute(&line) {
+            Ok(output) if output.is_empty() => (),
+            Ok(output) => println!("{}", output),
+            Err(err) => eprintln!("{}", err),
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_session() {
+    let mut session = Session::new(crate::registry(), runner::DEFAULT_TIMEOUT);
+
+    assert!(session.execute("run").is_err());
+    assert!(session.execute("load 99").is_err());
+
+    session.text = "35\n20\n15\n25\n47\n40\n62\n55\n65\n95\n102\n117\n150\n182\n127\n".to_string();
+    session.day_num = Some(9);
+    assert!(session.parse().is_ok());
+
+    assert!(session.execute("set bogus=1").is_err());
+    assert!(session.execute("set preamble=5").is_ok());
+    assert!(session
+        .execute("run 1")
+        .unwrap()
+        .starts_with("Part 1: 127 "));
+    assert_eq!(
+        session.execute("params").unwrap(),
+        "preamble=5 (default 25)"
+    );
+
+    session.execute("unset preamble").unwrap();
+    assert!(session
+        .execute("run 1")
+        .unwrap()
+        .starts_with("Part 1: no answer found"));
+    assert!(session.execute("show numbers").is_err());
+
+    session.text = "-4000000000000\n4000000002020\n".to_string();
+    session.day_num = Some(1);
+    session.params.clear();
+    assert!(session.execute("set width=i64").is_ok());
+    assert_eq!(
+        session.execute("run 1"),
+        Err("Part 1: overflow: product of entries [0, 1] overflows i64".to_string())
+    );
+
+    session.text = "0,3,6\n".to_string();
+    session.day_num = Some(15);
+    session.params.clear();
+    session.timeout = Duration::from_millis(10);
+    assert!(session.parse().is_ok());
+    assert_eq!(
+        session.execute("run 2"),
+        Err("Part 2: timed out after 0.01s".to_string())
+    );
+}
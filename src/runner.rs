@@ -0,0 +1,507 @@
+use crate::{
+    answer::Answer,
+    cancel::CancelToken,
+    error::{ParseError, SolveError},
+    input::Input,
+    solver::Registry,
+};
+use std::{
+    collections::{BTreeMap, VecDeque},
+    fmt,
+    panic::{self, AssertUnwindSafe},
+    path::PathBuf,
+    sync::{
+        mpsc::{self, RecvTimeoutError, Sender},
+        Arc, Mutex,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+pub const PART_NUMS: [u8; 2] = [1, 2];
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RunError {
+    Input(String),
+    Parse(ParseError),
+    NoAnswer,
+    TimedOut(Duration),
+    Solve(SolveError),
+    Panicked,
+}
+
+impl fmt::Display for RunError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RunError::Input(message) => write!(f, "could not read input: {}", message),
+            RunError::Parse(error) => write!(f, "parse error: {}", error),
+            RunError::NoAnswer => write!(f, "no answer found"),
+            RunError::TimedOut(timeout) => {
+                write!(f, "timed out after {}s", timeout.as_secs_f64())
+            }
+            RunError::Solve(error) => write!(f, "{}", error),
+            RunError::Panicked => write!(f, "solver panicked"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PartRun {
+    pub day_num: u8,
+    pub part_num: u8,
+    pub answer: Result<Answer, RunError>,
+    pub diagnostics: Vec<ParseError>,
+    pub parse_time: Duration,
+    pub solve_time: Duration,
+}
+
+pub fn data_path(day_num: u8) -> PathBuf {
+    [
+        env!("CARGO_MANIFEST_DIR"),
+        "data",
+        &format!("day{}.txt", day_num),
+    ]
+    .iter()
+    .collect()
+}
+
+pub fn diagnostics(input: &Input, error: Option<&ParseError>) -> Vec<ParseError> {
+    let mut diagnostics = input.report().errors;
+
+    if let Some(error) = error {
+        if !diagnostics.contains(error) {
+            diagnostics.push(error.clone());
+        }
+    }
+
+    diagnostics
+}
+
+pub fn run_parts(
+    registry: &Registry,
+    day_num: u8,
+    part_nums: &[u8],
+    input: &Input,
+) -> Vec<PartRun> {
+    run_parts_cancellable(registry, day_num, part_nums, input, &CancelToken::new())
+}
+
+pub fn run_parts_cancellable(
+    registry: &Registry,
+    day_num: u8,
+    part_nums: &[u8],
+    input: &Input,
+    token: &CancelToken,
+) -> Vec<PartRun> {
+    let mut runs = vec![];
+    run_parts_with(registry, day_num, part_nums, input, token, |run| {
+        runs.push(run)
+    });
+    runs
+}
+
+pub fn run_parts_with_timeout(
+    registry: &Registry,
+    day_num: u8,
+    part_nums: &[u8],
+    input: Input,
+    timeout: Option<Duration>,
+) -> Vec<PartRun> {
+    let token = timeout.map_or_else(CancelToken::new, CancelToken::with_timeout);
+    let (sender, receiver) = mpsc::channel();
+
+    {
+        let registry = registry.clone();
+        let part_nums = part_nums.to_vec();
+        let token = token.clone();
+
+        thread::spawn(move || {
+            let runs = run_parts_cancellable(&registry, day_num, &part_nums, &input, &token);
+            let _ = sender.send(runs);
+        });
+    }
+
+    let result = match timeout {
+        Some(timeout) => receiver.recv_timeout(timeout),
+        None => receiver.recv().map_err(|_| RecvTimeoutError::Disconnected),
+    };
+
+    let error = match result {
+        Ok(runs) => return runs,
+        Err(RecvTimeoutError::Timeout) => {
+            token.cancel();
+            RunError::TimedOut(timeout.unwrap_or_default())
+        }
+        Err(RecvTimeoutError::Disconnected) => RunError::Panicked,
+    };
+
+    part_nums
+        .iter()
+        .map(|part_num| failed_run(day_num, *part_num, error.clone()))
+        .collect()
+}
+
+fn run_parts_with<F>(
+    registry: &Registry,
+    day_num: u8,
+    part_nums: &[u8],
+    input: &Input,
+    token: &CancelToken,
+    mut on_run: F,
+) where
+    F: FnMut(PartRun),
+{
+    let timed_out = || RunError::TimedOut(token.timeout().unwrap_or_default());
+
+    let parse_start = Instant::now();
+    let solver = token.scope(|| registry.parse(day_num, input));
+    let parse_time = parse_start.elapsed();
+
+    let diagnostics = match &solver {
+        Some(Err(err)) => diagnostics(input, Some(err)),
+        _ => diagnostics(input, None),
+    };
+
+    for part_num in part_nums.iter().copied() {
+        let solve_start = Instant::now();
+        let answer = match &solver {
+            _ if token.is_cancelled() => Err(timed_out()),
+            Some(Ok(solver)) => match token.scope(|| solver.solve(part_num)) {
+                _ if token.is_cancelled() => Err(timed_out()),
+                Ok(answer) => answer.ok_or(RunError::NoAnswer),
+                Err(err) => Err(RunError::Solve(err)),
+            },
+            Some(Err(err)) => Err(RunError::Parse(err.clone())),
+            None => Err(RunError::NoAnswer),
+        };
+
+        on_run(PartRun {
+            day_num,
+            part_num,
+            answer,
+            diagnostics: diagnostics.clone(),
+            parse_time,
+            solve_time: solve_start.elapsed(),
+        });
+    }
+}
+
+fn failed_run(day_num: u8, part_num: u8, error: RunError) -> PartRun {
+    PartRun {
+        day_num,
+        part_num,
+        answer: Err(error),
+        diagnostics: vec![],
+        parse_time: Duration::default(),
+        solve_time: Duration::default(),
+    }
+}
+
+enum Event {
+    Started(usize, CancelToken),
+    Run(usize, PartRun),
+    Finished(usize, bool),
+}
+
+fn spawn_worker<F>(
+    registry: Registry,
+    day_nums: Arc<Vec<u8>>,
+    queue: Arc<Mutex<VecDeque<usize>>>,
+    load_input: Arc<F>,
+    timeout: Option<Duration>,
+    events: Sender<Event>,
+) where
+    F: Fn(u8) -> Result<Input, String> + Send + Sync + 'static,
+{
+    thread::spawn(move || loop {
+        let index = match queue.lock().unwrap().pop_front() {
+            Some(index) => index,
+            None => break,
+        };
+
+        let day_num = day_nums[index];
+        let token = timeout.map_or_else(CancelToken::new, CancelToken::with_timeout);
+        if events.send(Event::Started(index, token.clone())).is_err() {
+            break;
+        }
+
+        let result = panic::catch_unwind(AssertUnwindSafe(|| match load_input(day_num) {
+            Ok(input) => run_parts_with(&registry, day_num, &PART_NUMS, &input, &token, |run| {
+                let _ = events.send(Event::Run(index, run));
+            }),
+            Err(message) => {
+                for part_num in PART_NUMS.iter().copied() {
+                    let run = failed_run(day_num, part_num, RunError::Input(message.clone()));
+                    let _ = events.send(Event::Run(index, run));
+                }
+            }
+        }));
+
+        if events
+            .send(Event::Finished(index, result.is_err()))
+            .is_err()
+            || token.is_cancelled()
+        {
+            break;
+        }
+    });
+}
+
+pub fn run_days_parallel<F>(
+    registry: &Registry,
+    day_nums: &[u8],
+    jobs: usize,
+    timeout: Option<Duration>,
+    load_input: F,
+) -> Vec<PartRun>
+where
+    F: Fn(u8) -> Result<Input, String> + Send + Sync + 'static,
+{
+    let day_nums = Arc::new(day_nums.to_vec());
+    let queue = Arc::new(Mutex::new((0..day_nums.len()).collect::<VecDeque<usize>>()));
+    let load_input = Arc::new(load_input);
+    let (sender, receiver) = mpsc::channel();
+
+    let spawn = || {
+        spawn_worker(
+            registry.clone(),
+            Arc::clone(&day_nums),
+            Arc::clone(&queue),
+            Arc::clone(&load_input),
+            timeout,
+            sender.clone(),
+        )
+    };
+
+    for _ in 0..jobs.clamp(1, day_nums.len().max(1)) {
+        spawn();
+    }
+
+    let mut started: BTreeMap<usize, (Instant, CancelToken)> = BTreeMap::new();
+    let mut runs: BTreeMap<(usize, u8), PartRun> = BTreeMap::new();
+    let mut remaining = day_nums.len();
+
+    let finish = |index: usize, runs: &mut BTreeMap<(usize, u8), PartRun>, error: RunError| {
+        for part_num in PART_NUMS.iter().copied() {
+            runs.entry((index, part_num))
+                .or_insert_with(|| failed_run(day_nums[index], part_num, error.clone()));
+        }
+    };
+
+    while remaining > 0 {
+        let deadline = timeout.and_then(|timeout| {
+            started
+                .values()
+                .map(|(started_at, _)| *started_at)
+                .min()
+                .map(|started_at| started_at + timeout)
+        });
+
+        let event = match deadline {
+            Some(deadline) => {
+                receiver.recv_timeout(deadline.saturating_duration_since(Instant::now()))
+            }
+            None => receiver.recv().map_err(|_| RecvTimeoutError::Disconnected),
+        };
+
+        match event {
+            Ok(Event::Started(index, token)) => {
+                started.insert(index, (Instant::now(), token));
+            }
+            Ok(Event::Run(index, run)) => {
+                if started.contains_key(&index) {
+                    runs.insert((index, run.part_num), run);
+                }
+            }
+            Ok(Event::Finished(index, panicked)) => {
+                if started.remove(&index).is_some() {
+                    if panicked {
+                        finish(index, &mut runs, RunError::Panicked);
+                    }
+                    remaining -= 1;
+                }
+            }
+            Err(RecvTimeoutError::Timeout) => {
+                let timeout = timeout.unwrap_or_default();
+                let timed_out = started
+                    .iter()
+                    .filter(|(_, (started_at, _))| started_at.elapsed() >= timeout)
+                    .map(|(index, _)| *index)
+                    .collect::<Vec<usize>>();
+
+                for index in timed_out {
+                    if let Some((_, token)) = started.remove(&index) {
+                        token.cancel();
+                    }
+                    finish(index, &mut runs, RunError::TimedOut(timeout));
+                    remaining -= 1;
+
+                    spawn();
+                }
+            }
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+    }
+
+    runs.into_values().collect()
+}
+
+#[cfg(test)]
+pub fn run_example(day_num: u8, part_num: u8, text: &str, params: &[(&str, &str)]) -> String {
+    let input = params
+        .iter()
+        .fold(Input::new(text), |input, (name, value)| {
+            input.with_param(name, value)
+        });
+
+    let run = run_parts(&crate::registry(), day_num, &[part_num], &input).remove(0);
+
+    match run.answer {
+        Ok(answer) => answer.to_string(),
+        Err(err) => err.to_string(),
+    }
+}
+
+#[cfg(test)]
+struct Sleeper {
+    millis: u64,
+}
+
+#[cfg(test)]
+impl crate::solver::Solver for Sleeper {
+    fn parse(input: &Input) -> Result<Self, ParseError> {
+        Ok(Self {
+            millis: input.text().trim().parse()?,
+        })
+    }
+
+    fn part1(&self) -> Result<Option<Answer>, SolveError> {
+        Ok(Some(Answer::from(self.millis)))
+    }
+
+    fn part2(&self) -> Result<Option<Answer>, SolveError> {
+        thread::sleep(Duration::from_millis(self.millis));
+        Ok(Some(Answer::from(self.millis)))
+    }
+}
+
+#[cfg(test)]
+static SPINNING: Mutex<(usize, usize)> = Mutex::new((0, 0));
+
+#[cfg(test)]
+struct Spinner;
+
+#[cfg(test)]
+impl crate::solver::Solver for Spinner {
+    fn parse(_input: &Input) -> Result<Self, ParseError> {
+        Ok(Self)
+    }
+
+    fn part1(&self) -> Result<Option<Answer>, SolveError> {
+        {
+            let mut spinning = SPINNING.lock().unwrap();
+            spinning.0 += 1;
+            spinning.1 = spinning.1.max(spinning.0);
+        }
+
+        while !crate::cancel::is_cancelled() {
+            thread::sleep(Duration::from_millis(1));
+        }
+
+        SPINNING.lock().unwrap().0 -= 1;
+        Ok(None)
+    }
+
+    fn part2(&self) -> Result<Option<Answer>, SolveError> {
+        Ok(None)
+    }
+}
+
+#[test]
+fn test_run_days_parallel() {
+    let mut registry = Registry::new();
+    registry.register::<Sleeper>(1);
+    registry.register::<Sleeper>(2);
+    registry.register::<Sleeper>(3);
+
+    let runs = run_days_parallel(
+        &registry,
+        &[3, 1, 2],
+        2,
+        Some(Duration::from_millis(500)),
+        |day_num| match day_num {
+            1 => Ok(Input::new("5000")),
+            2 => Ok(Input::new("1")),
+            _ => Err("missing".to_string()),
+        },
+    );
+
+    let answers = runs
+        .iter()
+        .map(|run| (run.day_num, run.part_num, run.answer.clone()))
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        answers,
+        vec![
+            (3, 1, Err(RunError::Input("missing".to_string()))),
+            (3, 2, Err(RunError::Input("missing".to_string()))),
+            (1, 1, Ok(Answer::from(5000))),
+            (1, 2, Err(RunError::TimedOut(Duration::from_millis(500)))),
+            (2, 1, Ok(Answer::from(1))),
+            (2, 2, Ok(Answer::from(1))),
+        ]
+    );
+}
+
+#[test]
+fn test_run_parts_with_timeout() {
+    let mut registry = Registry::new();
+    registry.register::<Sleeper>(1);
+
+    let start = Instant::now();
+    let runs = run_parts_with_timeout(
+        &registry,
+        1,
+        &PART_NUMS,
+        Input::new("60000"),
+        Some(Duration::from_millis(100)),
+    );
+
+    assert!(start.elapsed() < Duration::from_secs(5));
+    assert_eq!(
+        runs.iter()
+            .map(|run| run.answer.clone())
+            .collect::<Vec<_>>(),
+        vec![
+            Err(RunError::TimedOut(Duration::from_millis(100))),
+            Err(RunError::TimedOut(Duration::from_millis(100))),
+        ]
+    );
+
+    let runs = run_parts_with_timeout(&registry, 1, &[1], Input::new("5"), None);
+    assert_eq!(runs[0].answer, Ok(Answer::from(5)));
+}
+
+#[test]
+fn test_run_days_parallel_cancelled_workers_exit() {
+    let mut registry = Registry::new();
+    for day_num in 1..=6 {
+        registry.register::<Spinner>(day_num);
+    }
+
+    let runs = run_days_parallel(
+        &registry,
+        &[1, 2, 3, 4, 5, 6],
+        1,
+        Some(Duration::from_millis(50)),
+        |_| Ok(Input::new("")),
+    );
+
+    assert_eq!(runs.len(), 12);
+    assert!(runs
+        .iter()
+        .all(|run| run.answer == Err(RunError::TimedOut(Duration::from_millis(50)))));
+    assert!(SPINNING.lock().unwrap().1 <= 2);
+}
@@ -0,0 +1,176 @@
+use std::{
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+pub fn module_source(day_num: u8) -> String {
+    format!(
+        "use crate::{{
+    answer::Answer,
+    error::{{ParseError, SolveError}},
+    input::Input,
+    solver::{{Registry, Solver}},
+}};
+
+pub struct Day{day_num} {{
+    lines: Vec<String>,
+}}
+
+impl Solver for Day{day_num} {{
+    fn parse(input: &Input) -> Result<Self, ParseError> {{
+        Ok(Self {{
+            lines: input.lines::<String>()?,
+        }})
+    }}
+
+    fn part1(&self) -> Result<Option<Answer>, SolveError> {{
+        Ok(None)
+    }}
+
+    fn part2(&self) -> Result<Option<Answer>, SolveError> {{
+        Ok(None)
+    }}
+
+    fn values(&self) -> Vec<&'static str> {{
+        vec![\"lines\"]
+    }}
+
+    fn value(&self, name: &str, _args: &[&str]) -> Result<String, String> {{
+        match name {{
+            \"lines\" => Ok(self.lines.join(\"\\n\")),
+            _ => Err(format!(\"unknown value '{{}}'\", name)),
+        }}
+    }}
+}}
+
+pub fn register(registry: &mut Registry) {{
+    registry.register::<Day{day_num}>({day_num});
+}}
+
+#[test]
+fn test_examples() {{
+    let example = \"\";
+
+    assert_eq!(
+        crate::runner::run_example({day_num}, 1, example, &[]),
+        \"no answer found\"
+    );
+    assert_eq!(
+        crate::runner::run_example({day_num}, 2, example, &[]),
+        \"no answer found\"
+    );
+}}
+",
+        day_num = day_num
+    )
+}
+
+fn register_line_day(line: &str) -> Option<u8> {
+    line.trim()
+        .strip_prefix("day")?
+        .strip_suffix("::register(&mut registry);")?
+        .parse()
+        .ok()
+}
+
+pub fn register_module(lib_source: &str, day_num: u8) -> Result<String, String> {
+    let mod_line = format!("pub mod day{};", day_num);
+    let register_line = format!("    day{}::register(&mut registry);", day_num);
+
+    let mut lines = lib_source
+        .lines()
+        .map(String::from)
+        .collect::<Vec<String>>();
+
+    if lines.contains(&mod_line) {
+        return Err(format!("day{} is already declared", day_num));
+    }
+
+    let module = format!("day{}", day_num);
+    let mod_lines = lines
+        .iter()
+        .enumerate()
+        .filter_map(|(index, line)| {
+            Some((index, line.strip_prefix("pub mod ")?.strip_suffix(';')?))
+        })
+        .collect::<Vec<(usize, &str)>>();
+
+    let mod_index = mod_lines
+        .iter()
+        .find(|(_, name)| *name > module.as_str())
+        .map(|(index, _)| *index)
+        .or_else(|| mod_lines.last().map(|(index, _)| index + 1))
+        .ok_or("no 'pub mod' declarations found")?;
+    lines.insert(mod_index, mod_line);
+
+    let register_lines = lines
+        .iter()
+        .enumerate()
+        .filter_map(|(index, line)| register_line_day(line).map(|day| (index, day)))
+        .collect::<Vec<(usize, u8)>>();
+
+    let register_index = register_lines
+        .iter()
+        .find(|(_, day)| *day > day_num)
+        .map(|(index, _)| *index)
+        .or_else(|| register_lines.last().map(|(index, _)| index + 1))
+        .ok_or("no 'day<day_num>::register' calls found")?;
+    lines.insert(register_index, register_line);
+
+    Ok(lines.join("\n") + "\n")
+}
+
+pub fn new_day(root: &Path, day_num: u8) -> io::Result<Vec<PathBuf>> {
+    let src_dir = root.join("src");
+    let module_path = src_dir.join(format!("day{}.rs", day_num));
+    let lib_path = src_dir.join("lib.rs");
+    let data_path = root.join("data").join(format!("day{}.txt", day_num));
+
+    let lib_source = register_module(&fs::read_to_string(&lib_path)?, day_num)
+        .map_err(|err| io::Error::new(io::ErrorKind::AlreadyExists, err))?;
+
+    OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&module_path)?
+        .write_all(module_source(day_num).as_bytes())?;
+
+    fs::write(&lib_path, lib_source)?;
+
+    let mut created = vec![module_path, lib_path];
+
+    if !data_path.exists() {
+        fs::create_dir_all(root.join("data"))?;
+        fs::write(&data_path, "")?;
+        created.push(data_path);
+    }
+
+    Ok(created)
+}
+
+#[test]
+fn test_register_module() {
+    let lib_source = "use solver::Registry;
+
+pub mod day1;
+pub mod day2;
+pub mod error;
+
+pub fn registry() -> Registry {
+    let mut registry = Registry::new();
+
+    day1::register(&mut registry);
+    day2::register(&mut registry);
+
+    registry
+}
+";
+
+    let registered = register_module(lib_source, 16).unwrap();
+
+    assert!(registered.contains("pub mod day1;\npub mod day16;\npub mod day2;\n"));
+    assert!(registered
+        .contains("    day2::register(&mut registry);\n    day16::register(&mut registry);\n\n"));
+    assert!(register_module(&registered, 16).is_err());
+}
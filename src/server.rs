@@ -0,0 +1,308 @@
+use crate::{
+    input::{ErrorPolicy, Input},
+    json,
+    runner::{self, RunError},
+    solver::Registry,
+};
+use serde_json::{json, Value};
+use std::{
+    io::{self, BufRead, BufReader, Write},
+    net::{TcpListener, TcpStream},
+    thread,
+    time::Duration,
+};
+
+pub const DEFAULT_PORT: u16 = 8020;
+
+const MAX_BODY_LEN: usize = 16 * 1024 * 1024;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Request {
+    pub method: String,
+    pub path: String,
+    pub query: Vec<(String, String)>,
+    pub body: String,
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+fn decode_component(component: &str) -> io::Result<String> {
+    let mut bytes = component.bytes();
+    let mut decoded = vec![];
+
+    while let Some(byte) = bytes.next() {
+        match byte {
+            b'+' => decoded.push(b' '),
+            b'%' => {
+                let hex = [bytes.next(), bytes.next()];
+                let digits = hex
+                    .iter()
+                    .map(|digit| digit.and_then(|digit| (digit as char).to_digit(16)))
+                    .collect::<Option<Vec<u32>>>()
+                    .ok_or_else(|| invalid_data("invalid percent-encoding in query"))?;
+                decoded.push((digits[0] * 16 + digits[1]) as u8);
+            }
+            _ => decoded.push(byte),
+        }
+    }
+
+    String::from_utf8(decoded).map_err(|_| invalid_data("query is not UTF-8"))
+}
+
+impl Request {
+    pub fn read<R: BufRead>(reader: &mut R) -> io::Result<Self> {
+        let mut request_line = String::new();
+        reader.read_line(&mut request_line)?;
+
+        let mut parts = request_line.split_whitespace();
+        let method = parts.next().ok_or_else(|| invalid_data("missing method"))?;
+        let target = parts.next().ok_or_else(|| invalid_data("missing path"))?;
+
+        let mut content_len = 0;
+        loop {
+            let mut header = String::new();
+            if reader.read_line(&mut header)? == 0 || header.trim_end().is_empty() {
+                break;
+            }
+
+            if let Some((name, value)) = header.split_once(':') {
+                if name.trim().eq_ignore_ascii_case("content-length") {
+                    content_len = value
+                        .trim()
+                        .parse::<usize>()
+                        .map_err(|_| invalid_data("invalid Content-Length"))?;
+                }
+            }
+        }
+
+        if content_len > MAX_BODY_LEN {
+            return Err(invalid_data("request body too large"));
+        }
+
+        let mut body = vec![0; content_len];
+        reader.read_exact(&mut body)?;
+
+        let (path, query) = target.split_once('?').unwrap_or((target, ""));
+        let query = query
+            .split('&')
+            .filter(|pair| !pair.is_empty())
+            .map(|pair| {
+                let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+                Ok((decode_component(name)?, decode_component(value)?))
+            })
+            .collect::<io::Result<Vec<(String, String)>>>()?;
+
+        Ok(Self {
+            method: method.to_string(),
+            path: path.to_string(),
+            query,
+            body: String::from_utf8(body).map_err(|_| invalid_data("body is not UTF-8"))?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Response {
+    pub status: u16,
+    pub body: Value,
+}
+
+impl Response {
+    fn error(status: u16, message: &str) -> Self {
+        Self {
+            status,
+            body: json!({ "error": message }),
+        }
+    }
+
+    fn reason(&self) -> &'static str {
+        match self.status {
+            200 => "OK",
+            400 => "Bad Request",
+            404 => "Not Found",
+            405 => "Method Not Allowed",
+            422 => "Unprocessable Entity",
+            500 => "Internal Server Error",
+            504 => "Gateway Timeout",
+            _ => "Unknown",
+        }
+    }
+
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        let body = self.body.to_string();
+
+        write!(
+            writer,
+            "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            self.status,
+            self.reason(),
+            body.len(),
+            body
+        )?;
+        writer.flush()
+    }
+}
+
+fn parse_route(path: &str) -> Option<(u8, u8)> {
+    let segments = path.trim_matches('/').split('/').collect::<Vec<&str>>();
+
+    match segments.as_slice() {
+        ["day", day_num, "part", part_num] => Some((day_num.parse().ok()?, part_num.parse().ok()?)),
+        _ => None,
+    }
+}
+
+pub fn handle(registry: &Registry, request: &Request, timeout: Option<Duration>) -> Response {
+    let (day_num, part_num) = match parse_route(&request.path) {
+        Some(route) => route,
+        None => return Response::error(404, "expected /day/<day_num>/part/<part_num>"),
+    };
+
+    if request.method != "POST" {
+        return Response::error(405, "expected POST with the puzzle input as the body");
+    }
+
+    if !registry.contains(day_num) || !runner::PART_NUMS.contains(&part_num) {
+        return Response::error(
+            404,
+            &format!("no solver for day {} part {}", day_num, part_num),
+        );
+    }
+
+    let params = registry.params(day_num);
+    let mut input = Input::new(request.body.as_str());
+
+    for (name, value) in &request.query {
+        if name == "on_error" {
+            match value.parse::<ErrorPolicy>() {
+                Ok(policy) => input = input.with_policy(policy),
+                Err(err) => return Response::error(400, &err.to_string()),
+            }
+        } else if params.iter().any(|param| param.name == name) {
+            input = input.with_param(name, value);
+        } else {
+            return Response::error(400, &format!("unknown parameter '{}'", name));
+        }
+    }
+
+    let run =
+        runner::run_parts_with_timeout(registry, day_num, &[part_num], input, timeout).remove(0);
+
+    Response {
+        status: match &run.answer {
+            Ok(_) | Err(RunError::NoAnswer) => 200,
+            Err(RunError::Parse(_)) | Err(RunError::Input(_)) | Err(RunError::Solve(_)) => 422,
+            Err(RunError::TimedOut(_)) => 504,
+            Err(RunError::Panicked) => 500,
+        },
+        body: json::part_run(&run),
+    }
+}
+
+fn handle_connection(registry: &Registry, stream: TcpStream, timeout: Option<Duration>) {
+    let mut reader = BufReader::new(&stream);
+
+    let response = match Request::read(&mut reader) {
+        Ok(request) => handle(registry, &request, timeout),
+        Err(err) => Response::error(400, &err.to_string()),
+    };
+
+    if let Err(err) = response.write_to(&mut &stream) {
+        crate::warn!("Could not write response: {}", err);
+    }
+}
+
+pub fn serve(registry: &Registry, port: u16, timeout: Duration) -> io::Result<()> {
+    let listener = TcpListener::bind(("127.0.0.1", port))?;
+    println!("Listening on http://{}", listener.local_addr()?);
+
+    for stream in listener.incoming() {
+        match stream {
+            Ok(stream) => {
+                let registry = registry.clone();
+                thread::spawn(move || handle_connection(&registry, stream, Some(timeout)));
+            }
+            Err(err) => crate::warn!("Could not accept connection: {}", err),
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_read_request() {
+    let raw = "POST /day/9/part/1?preamble=5 HTTP/1.1\r\nHost: localhost\r\nContent-Length: 5\r\n\r\n1\n2\n3";
+    let request = Request::read(&mut raw.as_bytes()).unwrap();
+
+    assert_eq!(request.method, "POST");
+    assert_eq!(request.path, "/day/9/part/1");
+    assert_eq!(
+        request.query,
+        vec![("preamble".to_string(), "5".to_string())]
+    );
+    assert_eq!(request.body, "1\n2\n3");
+
+    let raw = "POST /day/7/part/2?bag=bright%20white&slopes=3,1+1%2C2 HTTP/1.1\r\n\r\n";
+    let request = Request::read(&mut raw.as_bytes()).unwrap();
+    assert_eq!(
+        request.query,
+        vec![
+            ("bag".to_string(), "bright white".to_string()),
+            ("slopes".to_string(), "3,1 1,2".to_string()),
+        ]
+    );
+
+    let raw = "POST /day/7/part/2?bag=bright%2 HTTP/1.1\r\n\r\n";
+    assert!(Request::read(&mut raw.as_bytes()).is_err());
+}
+
+#[test]
+fn test_handle() {
+    let registry = crate::registry();
+    let request = |method: &str, path: &str, body: &str| Request {
+        method: method.to_string(),
+        path: path.to_string(),
+        query: vec![],
+        body: body.to_string(),
+    };
+
+    let response = handle(
+        &registry,
+        &request("POST", "/day/1/part/1", "1721\n299\n"),
+        None,
+    );
+    assert_eq!(response.status, 200);
+    assert_eq!(response.body["answer"], json!(514579));
+
+    let response = handle(&registry, &request("POST", "/day/1/part/1", "x\n"), None);
+    assert_eq!(response.status, 422);
+    assert_eq!(response.body["diagnostics"][0]["line"], json!(1));
+
+    assert_eq!(
+        handle(&registry, &request("GET", "/day/1/part/1", ""), None).status,
+        405
+    );
+    assert_eq!(
+        handle(&registry, &request("POST", "/day/1/part/3", ""), None).status,
+        404
+    );
+    assert_eq!(
+        handle(&registry, &request("POST", "/days", ""), None).status,
+        404
+    );
+
+    let mut with_param = request("POST", "/day/1/part/1", "1\n2\n");
+    with_param.query = vec![("bogus".to_string(), "1".to_string())];
+    assert_eq!(handle(&registry, &with_param, None).status, 400);
+
+    let mut overflow = request("POST", "/day/1/part/1", "-4000000000000\n4000000002020\n");
+    overflow.query = vec![("width".to_string(), "i64".to_string())];
+    let response = handle(&registry, &overflow, None);
+    assert_eq!(response.status, 422);
+    assert_eq!(
+        response.body["error"],
+        json!("overflow: product of entries [0, 1] overflows i64")
+    );
+}
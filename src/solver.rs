@@ -0,0 +1,136 @@
+use crate::{
+    answer::Answer,
+    error::{ParseError, SolveError},
+    generate::Rng,
+    input::Input,
+};
+use std::{collections::BTreeMap, str::FromStr};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Param {
+    pub name: &'static str,
+    pub default: String,
+}
+
+impl Param {
+    pub fn new<T: ToString>(name: &'static str, default: T) -> Self {
+        Self {
+            name,
+            default: default.to_string(),
+        }
+    }
+}
+
+pub trait Solver {
+    fn params() -> Vec<Param>
+    where
+        Self: Sized,
+    {
+        vec![]
+    }
+
+    fn parse(input: &Input) -> Result<Self, ParseError>
+    where
+        Self: Sized;
+
+    fn generate(_rng: &mut Rng, _size: usize) -> Option<String>
+    where
+        Self: Sized,
+    {
+        None
+    }
+
+    fn part1(&self) -> Result<Option<Answer>, SolveError>;
+
+    fn part2(&self) -> Result<Option<Answer>, SolveError>;
+
+    fn values(&self) -> Vec<&'static str> {
+        vec![]
+    }
+
+    fn value(&self, name: &str, _args: &[&str]) -> Result<String, String> {
+        Err(format!("unknown value '{}'", name))
+    }
+
+    fn solve(&self, part_num: u8) -> Result<Option<Answer>, SolveError> {
+        match part_num {
+            1 => self.part1(),
+            2 => self.part2(),
+            _ => Ok(None),
+        }
+    }
+}
+
+pub fn value_arg<T: FromStr>(args: &[&str], index: usize, default: T) -> Result<T, String> {
+    match args.get(index) {
+        Some(arg) => arg
+            .parse::<T>()
+            .map_err(|_| format!("invalid argument '{}'", arg)),
+        None => Ok(default),
+    }
+}
+
+pub type ParseFn = fn(&Input) -> Result<Box<dyn Solver>, ParseError>;
+
+pub type ParamsFn = fn() -> Vec<Param>;
+
+pub type GenerateFn = fn(&mut Rng, usize) -> Option<String>;
+
+#[derive(Clone)]
+struct Entry {
+    parse: ParseFn,
+    params: ParamsFn,
+    generate: GenerateFn,
+}
+
+fn parse_boxed<S: Solver + 'static>(input: &Input) -> Result<Box<dyn Solver>, ParseError> {
+    Ok(Box::new(S::parse(input)?))
+}
+
+#[derive(Clone, Default)]
+pub struct Registry {
+    solvers: BTreeMap<u8, Entry>,
+}
+
+impl Registry {
+    pub fn new() -> Self {
+        Self {
+            solvers: BTreeMap::new(),
+        }
+    }
+
+    pub fn register<S: Solver + 'static>(&mut self, day_num: u8) {
+        let entry = Entry {
+            parse: parse_boxed::<S>,
+            params: S::params,
+            generate: S::generate,
+        };
+
+        let previous = self.solvers.insert(day_num, entry);
+        assert!(previous.is_none(), "Day {} registered twice", day_num);
+    }
+
+    pub fn days(&self) -> Vec<u8> {
+        self.solvers.keys().copied().collect()
+    }
+
+    pub fn contains(&self, day_num: u8) -> bool {
+        self.solvers.contains_key(&day_num)
+    }
+
+    pub fn params(&self, day_num: u8) -> Vec<Param> {
+        self.solvers
+            .get(&day_num)
+            .map_or_else(Vec::new, |entry| (entry.params)())
+    }
+
+    pub fn generate(&self, day_num: u8, rng: &mut Rng, size: usize) -> Option<String> {
+        self.solvers
+            .get(&day_num)
+            .and_then(|entry| (entry.generate)(rng, size))
+    }
+
+    pub fn parse(&self, day_num: u8, input: &Input) -> Option<Result<Box<dyn Solver>, ParseError>> {
+        self.solvers.get(&day_num).map(|entry| (entry.parse)(input))
+    }
+}
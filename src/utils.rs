@@ -1,23 +1,69 @@
+use crate::error::ParseError;
 use std::{
-    io::{self, BufRead},
     ops::{BitAnd, BitOr, BitXor, Shl, Shr},
     str::FromStr,
 };
 
-pub fn stdin_to_vec<T: FromStr>() -> Vec<T> {
-    let mut list: Vec<T> = vec![];
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RecordLine<'a> {
+    pub line_num: usize,
+    pub text: &'a str,
+}
+
+pub fn record_groups(input: &str) -> Vec<Vec<RecordLine<'_>>> {
+    let mut groups: Vec<Vec<RecordLine>> = vec![];
+    let mut group: Vec<RecordLine> = vec![];
 
-    for line_result in io::stdin().lock().lines() {
-        let line = line_result.unwrap();
-        let item = line.parse::<T>();
+    for (line_index, line) in input.lines().enumerate() {
+        let text = line.trim_end();
 
-        match item {
-            Ok(value) => list.push(value),
-            Err(_) => println!("Could not parse line: '{}'.", line),
+        if text.is_empty() {
+            if !group.is_empty() {
+                groups.push(group);
+                group = vec![];
+            }
+            continue;
         }
+
+        group.push(RecordLine {
+            line_num: line_index + 1,
+            text,
+        });
     }
 
-    list
+    if !group.is_empty() {
+        groups.push(group);
+    }
+
+    groups
+}
+
+pub fn record_tokens<'a>(
+    group: &'a [RecordLine<'a>],
+) -> impl Iterator<Item = (&'a RecordLine<'a>, &'a str)> {
+    group.iter().flat_map(|record_line| {
+        record_line
+            .text
+            .split_whitespace()
+            .map(move |token| (record_line, token))
+    })
+}
+
+pub fn parse_pair<T>(s: &str) -> Result<(T, T), ParseError>
+where
+    T: FromStr,
+    T::Err: Into<ParseError>,
+{
+    let (first, second) = s
+        .split_once(',')
+        .ok_or_else(|| ParseError::at(s, s, "a pair like '<x>,<y>'"))?;
+
+    let parse = |part: &str| {
+        part.parse::<T>()
+            .map_err(|err| ParseError::at(s, part, &err.into().expected))
+    };
+
+    Ok((parse(first)?, parse(second)?))
 }
 
 pub fn check_bit<T: From<u8> + PartialEq + BitAnd<Output = T> + Shr<Output = T> + Copy>(
@@ -47,3 +93,51 @@ pub fn set_bit<
         num & toggle_bit(num, position)
     }
 }
+
+#[test]
+fn test_record_groups() {
+    let groups = record_groups("abc\r\nd  \r\n\r\n \r\n\r\ne\r\n\r\n\r\n");
+
+    assert_eq!(groups.len(), 2);
+    assert_eq!(
+        groups[0],
+        vec![
+            RecordLine {
+                line_num: 1,
+                text: "abc"
+            },
+            RecordLine {
+                line_num: 2,
+                text: "d"
+            },
+        ]
+    );
+    assert_eq!(
+        groups[1],
+        vec![RecordLine {
+            line_num: 6,
+            text: "e"
+        }]
+    );
+}
+
+#[test]
+fn test_record_tokens() {
+    let groups = record_groups("a:1 b:2\nc:3\n");
+    let tokens = record_tokens(&groups[0])
+        .map(|(record_line, token)| (record_line.line_num, token))
+        .collect::<Vec<(usize, &str)>>();
+
+    assert_eq!(tokens, vec![(1, "a:1"), (1, "b:2"), (2, "c:3")]);
+}
+
+#[test]
+fn test_parse_pair() {
+    assert_eq!(parse_pair::<i32>("10,-1"), Ok((10, -1)));
+
+    let error = parse_pair::<usize>("3,x").unwrap_err();
+    assert_eq!((error.column, error.text.as_str()), (3, "x"));
+
+    let error = parse_pair::<usize>("3").unwrap_err();
+    assert_eq!(error.expected, "a pair like '<x>,<y>'");
+}